@@ -1,10 +1,25 @@
 pub mod shared;
 pub mod wind;
+pub mod wind_data;
 pub mod bpm;
+pub mod bpm_data;
+pub mod bpm_video;
 pub mod clouds;
 pub mod rgb;
+pub mod rgb_data;
+pub mod rgb_layout;
+pub mod palette;
+pub mod color;
 pub mod text;
 pub mod slideshow;
+pub mod preview;
+pub mod loudness;
+pub mod audio;
+pub mod dsp;
+pub mod gust;
+pub mod interp;
+pub mod midi;
+pub mod compose;
 
 pub use wind::convert_wind;
 pub use bpm::convert_bpm;
@@ -12,6 +27,7 @@ pub use clouds::convert_clouds;
 pub use rgb::convert_rgb;
 pub use text::convert_text;
 pub use slideshow::convert_slideshow;
+pub use compose::convert_mix;
 
 use crossbeam_channel::Sender;
 use std::sync::atomic::AtomicBool;
@@ -23,6 +39,8 @@ pub enum Progress {
     Start { name: String },
     Update { name: String, fraction: f32 },
     Done { name: String },
+    /// The file converted, but something in its data needs a look
+    Warning { name: String, message: String },
     Error { name: String, error: String },
 }
 
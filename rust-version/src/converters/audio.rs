@@ -0,0 +1,367 @@
+use std::path::Path;
+
+use super::shared::Rng;
+
+/// Zero crossings on each side of the resampling kernel
+const SINC_ZEROS: usize = 32;
+/// Kernel table entries per zero crossing, linearly interpolated between
+const SINC_RESOLUTION: usize = 512;
+
+/// A decoded recording with one sample vector per channel.
+#[derive(Clone, Debug)]
+pub struct AudioClip {
+    pub sample_rate: u32,
+    pub channels: Vec<Vec<f32>>,
+}
+
+impl AudioClip {
+    pub fn frames(&self) -> usize {
+        self.channels.first().map_or(0, |c| c.len())
+    }
+
+    /// Average of all channels.
+    pub fn mono(&self) -> Vec<f32> {
+        let n = self.channels.len().max(1) as f32;
+        (0..self.frames())
+            .map(|i| self.channels.iter().map(|c| c[i]).sum::<f32>() / n)
+            .collect()
+    }
+
+    /// Keeps mono and stereo as they are and folds anything wider down to stereo,
+    /// assuming the WAV channel order L, R, C, LFE, Ls, Rs, ...
+    fn fold_to_stereo(mut self) -> Self {
+        if self.channels.len() <= 2 {
+            return self;
+        }
+        let frames = self.frames();
+        let side = std::f32::consts::FRAC_1_SQRT_2;
+        let mut left = vec![0.0f32; frames];
+        let mut right = vec![0.0f32; frames];
+        let mut norm_l = 0.0f32;
+        let mut norm_r = 0.0f32;
+        for (index, channel) in self.channels.iter().enumerate() {
+            let (gl, gr) = match index {
+                0 => (1.0, 0.0),
+                1 => (0.0, 1.0),
+                2 => (side, side),
+                3 => continue, // LFE
+                i if i % 2 == 0 => (side, 0.0),
+                _ => (0.0, side),
+            };
+            norm_l += gl;
+            norm_r += gr;
+            for i in 0..frames {
+                left[i] += channel[i] * gl;
+                right[i] += channel[i] * gr;
+            }
+        }
+        left.iter_mut().for_each(|s| *s /= norm_l.max(1.0));
+        right.iter_mut().for_each(|s| *s /= norm_r.max(1.0));
+        self.channels = vec![left, right];
+        self
+    }
+
+    /// Band-limited windowed-sinc conversion to another sample rate.
+    fn resampled(self, rate: u32) -> Self {
+        if self.sample_rate == rate || self.frames() == 0 {
+            return self;
+        }
+        let table = sinc_table();
+        let ratio = self.sample_rate as f64 / rate as f64;
+        // Lower the cutoff when decimating so nothing folds back below Nyquist
+        let scale = (1.0 / ratio).min(1.0);
+        let half_span = SINC_ZEROS as f64 / scale;
+        let out_frames = (self.frames() as f64 / ratio).floor() as usize;
+
+        let channels = self
+            .channels
+            .iter()
+            .map(|data| {
+                (0..out_frames)
+                    .map(|n| {
+                        let center = n as f64 * ratio;
+                        let first = (center - half_span).ceil().max(0.0) as usize;
+                        let last = ((center + half_span).floor() as usize).min(data.len() - 1);
+                        let mut acc = 0.0f64;
+                        for (i, &x) in data.iter().enumerate().take(last + 1).skip(first) {
+                            let pos = (center - i as f64).abs() * scale * SINC_RESOLUTION as f64;
+                            let idx = pos as usize;
+                            if idx + 1 >= table.len() {
+                                continue;
+                            }
+                            let frac = pos - idx as f64;
+                            let w = table[idx] as f64 * (1.0 - frac) + table[idx + 1] as f64 * frac;
+                            acc += x as f64 * w;
+                        }
+                        (acc * scale) as f32
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self { sample_rate: rate, channels }
+    }
+}
+
+/// One side of a Blackman-windowed sinc, sampled `SINC_RESOLUTION` times per zero crossing.
+fn sinc_table() -> Vec<f32> {
+    let len = SINC_ZEROS * SINC_RESOLUTION + 1;
+    (0..len)
+        .map(|i| {
+            let x = i as f64 / SINC_RESOLUTION as f64;
+            let sinc = if i == 0 {
+                1.0
+            } else {
+                (std::f64::consts::PI * x).sin() / (std::f64::consts::PI * x)
+            };
+            let phase = std::f64::consts::PI * (x / SINC_ZEROS as f64 + 1.0);
+            let window = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+            (sinc * window) as f32
+        })
+        .collect()
+}
+
+/// Reads a WAV file into separate channels scaled to -1.0..1.0.
+fn read_wav(path: &Path) -> Result<AudioClip, String> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| format!("open {}: {e}", path.display()))?;
+    
+    let spec = reader.spec();
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => {
+            reader.samples::<f32>().map(|s| s.unwrap_or(0.0)).collect()
+        }
+        hound::SampleFormat::Int => {
+            let max_val = match spec.bits_per_sample {
+                8 => 128.0,
+                16 => 32768.0,
+                24 => 8388608.0,
+                32 => 2147483648.0,
+                _ => 32768.0,
+            };
+            reader.samples::<i32>().map(|s| (s.unwrap_or(0) as f32) / max_val).collect()
+        }
+    };
+
+    let n_channels = spec.channels.max(1) as usize;
+    let channels = (0..n_channels)
+        .map(|ch| interleaved.iter().skip(ch).step_by(n_channels).cloned().collect())
+        .collect();
+    Ok(AudioClip { sample_rate: spec.sample_rate, channels })
+}
+
+/// Loads a recording for synthesis: mono or stereo, at `sample_rate`.
+pub fn load_clip(path: &Path, sample_rate: u32) -> Result<AudioClip, String> {
+    let clip = read_wav(path)?.fold_to_stereo().resampled(sample_rate);
+    if clip.frames() == 0 {
+        return Err(format!("{} contains no samples", path.display()));
+    }
+    Ok(clip)
+}
+
+/// Writes interleaved samples as a 32-bit float WAV for ffmpeg to encode.
+pub fn write_wav(path: &Path, samples: &[f32], channels: usize, sample_rate: u32) -> Result<(), String> {
+    let spec = hound::WavSpec {
+        channels: channels as u16,
+        sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut w = hound::WavWriter::create(path, spec).map_err(|e| format!("create {}: {e}", path.display()))?;
+    for s in samples {
+        w.write_sample(*s).map_err(|e| e.to_string())?;
+    }
+    w.finalize().map_err(|e| e.to_string())
+}
+
+/// How a recording is turned into an endless bed.
+#[derive(Clone, Debug)]
+pub struct LoopSettings {
+    /// Length of the equal-power crossfade at the seam and at random jumps
+    pub crossfade_ms: f32,
+    /// Jump to random positions inside the loop so it does not repeat audibly
+    pub random_offsets: bool,
+    /// Search for the best loop end instead of trusting the file boundaries
+    pub auto_detect: bool,
+    /// Seeds the random offsets, and the procedural wind and gust accents which
+    /// have nothing to loop
+    pub seed: u64,
+}
+
+impl Default for LoopSettings {
+    fn default() -> Self {
+        Self {
+            crossfade_ms: 250.0,
+            random_offsets: false,
+            auto_detect: false,
+            seed: 1,
+        }
+    }
+}
+
+/// Plays a recording forever without clicks at the wrap point.
+pub struct LoopPlayer {
+    buffers: Vec<Vec<f32>>,
+    len: usize,
+    fade: usize,
+    pos: usize,
+    /// Read position of the outgoing head while a jump crossfade runs
+    jump_from: Option<usize>,
+    jump_progress: usize,
+    until_jump: usize,
+    rng: Option<Rng>,
+}
+
+impl LoopPlayer {
+    pub fn new(clip: &AudioClip, settings: &LoopSettings, seed_offset: u64) -> Self {
+        let frames = clip.frames();
+        let requested = (settings.crossfade_ms.max(0.0) / 1000.0 * clip.sample_rate as f32) as usize;
+        // The seam needs room for two fades inside the loop
+        let fade = requested.min(frames / 4);
+
+        // Stereo recordings share the loop points found on their mid signal
+        let (start, end) = if settings.auto_detect {
+            find_loop_points(&clip.mono(), fade)
+        } else {
+            (0, frames)
+        };
+        let buffers: Vec<Vec<f32>> = clip.channels.iter().map(|c| seamless_loop(&c[start..end], fade)).collect();
+        let len = buffers.first().map_or(0, |b| b.len());
+
+        let mut rng = settings.random_offsets.then(|| Rng::new(settings.seed.wrapping_add(seed_offset)));
+        let until_jump = rng.as_mut().map_or(usize::MAX, |r| jump_interval(r, len));
+        let pos = rng.as_mut().map_or(0, |r| (r.next_f32() * len as f32) as usize % len.max(1));
+
+        Self { buffers, len, fade, pos, jump_from: None, jump_progress: 0, until_jump, rng }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Writes the next frame into `out`, spreading a mono recording over every channel.
+    pub fn next_frame(&mut self, out: &mut [f32]) {
+        if self.len == 0 {
+            out.iter_mut().for_each(|s| *s = 0.0);
+            return;
+        }
+
+        let fade_gains = self.jump_from.map(|from| {
+            let angle = self.jump_progress as f32 / self.fade.max(1) as f32 * std::f32::consts::FRAC_PI_2;
+            (from, angle.cos(), angle.sin())
+        });
+        for (ch, slot) in out.iter_mut().enumerate() {
+            let buffer = &self.buffers[ch.min(self.buffers.len() - 1)];
+            *slot = match fade_gains {
+                Some((from, out_gain, in_gain)) => buffer[from] * out_gain + buffer[self.pos] * in_gain,
+                None => buffer[self.pos],
+            };
+        }
+
+        if let Some(from) = self.jump_from {
+            self.jump_progress += 1;
+            self.jump_from = (self.jump_progress < self.fade).then_some((from + 1) % self.len);
+        }
+        self.pos = (self.pos + 1) % self.len;
+
+        if let Some(rng) = self.rng.as_mut() {
+            self.until_jump = self.until_jump.saturating_sub(1);
+            if self.until_jump == 0 && self.jump_from.is_none() {
+                self.jump_from = Some(self.pos);
+                self.jump_progress = 0;
+                self.pos = (rng.next_f32() * self.len as f32) as usize % self.len;
+                self.until_jump = jump_interval(rng, self.len);
+            }
+        }
+    }
+}
+
+/// Somewhere between half and all of the loop, so jumps never fall into a rhythm.
+fn jump_interval(rng: &mut Rng, len: usize) -> usize {
+    ((0.5 + 0.5 * rng.next_f32()) * len as f32) as usize + 1
+}
+
+/// Rearranges a loop so its tail fades into its head with an equal-power curve,
+/// making `buffer[len - 1] -> buffer[0]` continuous. The result is `fade` samples
+/// shorter than the input because the head is consumed by the crossfade.
+fn seamless_loop(data: &[f32], fade: usize) -> Vec<f32> {
+    if fade == 0 || data.len() <= 2 * fade {
+        return data.to_vec();
+    }
+    let len = data.len() - fade;
+    let mut buffer = Vec::with_capacity(len);
+    buffer.extend_from_slice(&data[fade..data.len() - fade]);
+    for j in 0..fade {
+        let angle = j as f32 / fade as f32 * std::f32::consts::FRAC_PI_2;
+        buffer.push(data[data.len() - fade + j] * angle.cos() + data[j] * angle.sin());
+    }
+    buffer
+}
+
+/// Finds the loop end whose outgoing material best matches the loop start, so the
+/// crossfade blends two similar, in-phase passages of an arbitrary recording.
+pub fn find_loop_points(data: &[f32], fade: usize) -> (usize, usize) {
+    let window = fade.clamp(256, 8192).min(data.len() / 4);
+    if window == 0 {
+        return (0, data.len());
+    }
+
+    // Start on the first rising zero crossing so the loop begins from silence
+    let search = (data.len() / 10).max(1);
+    let start = (1..search)
+        .find(|&i| data[i - 1] <= 0.0 && data[i] > 0.0)
+        .unwrap_or(0);
+
+    let head = &data[start..start + window];
+    let earliest = (start + data.len() / 2).max(start + 2 * window);
+    if earliest > data.len() {
+        return (start, data.len());
+    }
+
+    // Correlation favours in-phase material, the energy ratio keeps a quiet
+    // passage from being spliced onto a loud one
+    let score = |tail: &[f32], head: &[f32]| {
+        let (mut dot, mut ea, mut eb) = (0.0f64, 0.0f64, 0.0f64);
+        for (&a, &b) in tail.iter().zip(head) {
+            let (a, b) = (a as f64, b as f64);
+            dot += a * b;
+            ea += a * a;
+            eb += b * b;
+        }
+        if ea <= 0.0 || eb <= 0.0 {
+            return f64::MIN;
+        }
+        let correlation = dot / (ea * eb).sqrt();
+        let balance = ea.min(eb) / ea.max(eb);
+        correlation * balance.sqrt()
+    };
+
+    // Coarse pass over a box-averaged copy: the correlation peak of noisy wind is
+    // only a few samples wide, but its low-frequency outline survives decimation.
+    const DECIMATE: usize = 8;
+    let coarse: Vec<f32> = data
+        .chunks(DECIMATE)
+        .map(|c| c.iter().sum::<f32>() / c.len() as f32)
+        .collect();
+    let coarse_window = window / DECIMATE;
+    let coarse_start = start / DECIMATE;
+    let coarse_head = &coarse[coarse_start..coarse_start + coarse_window];
+    let mut best = (data.len(), f64::MIN);
+    for end_d in earliest.div_ceil(DECIMATE)..=data.len() / DECIMATE {
+        let s = score(&coarse[end_d - coarse_window..end_d], coarse_head);
+        if s > best.1 {
+            best = (end_d * DECIMATE, s);
+        }
+    }
+
+    let lo = best.0.saturating_sub(2 * DECIMATE).max(earliest);
+    let hi = (best.0 + 2 * DECIMATE).min(data.len());
+    let mut refined = (best.0, f64::MIN);
+    for end in lo..=hi {
+        let s = score(&data[end - window..end], head);
+        if s > refined.1 {
+            refined = (end, s);
+        }
+    }
+    (start, refined.0)
+}
@@ -1,55 +1,387 @@
 use std::fs;
-use std::path::Path;
-
-use super::{shared, CancelFlag, ProgressTx};
-
-/// Synthesizes a clean, realistic double-thump heartbeat.
-/// Returns a single beat cycle adjusted for the instantaneous BPM.
-fn generate_single_beat(current_bpm: f32, sample_rate: u32) -> Vec<f32> {
-    let beat_len_sec = 60.0 / current_bpm;
-    let num_samples = (beat_len_sec * sample_rate as f32) as usize;
-    let mut beat = vec![0.0; num_samples];
-
-    let lub_len_sec = 0.1_f32.min(beat_len_sec * 0.3);
-    let gap_len_sec = 0.05_f32.min(beat_len_sec * 0.1);
-    let dub_len_sec = 0.08_f32.min(beat_len_sec * 0.25);
-
-    let lub_samples = (lub_len_sec * sample_rate as f32) as usize;
-    let gap_samples = (gap_len_sec * sample_rate as f32) as usize;
-    let dub_samples = (dub_len_sec * sample_rate as f32) as usize;
-
-    let lub_freq = 45.0; 
-    let dub_freq = 55.0; 
-
-    // Louder amplitude multiplier
-    let amp_mult = 1.4;
-
-    // 1. Generate S1 (Lub)
-    for i in 0..lub_samples {
-        if i >= num_samples { break; }
-        let t = i as f32 / sample_rate as f32;
-        let env = (std::f32::consts::PI * i as f32 / lub_samples as f32).sin(); 
-        let wave = (2.0 * std::f32::consts::PI * lub_freq * t).sin();
-        beat[i] = (wave * env * 1.0 * amp_mult).clamp(-1.0, 1.0);
+use std::path::{Path, PathBuf};
+
+use super::audio;
+use super::bpm_data::BpmSeries;
+use super::bpm_video::{BeatVideo, BpmVideo};
+use super::dsp::Svf;
+use super::interp::{Interpolation, Series};
+use super::shared::{DataSpan, MemoryLease, Rng, Timeline};
+use super::{loudness, midi, preview, shared, CancelFlag, ProgressTx};
+
+/// The original double thump: two sine bursts under a half-sine window.
+#[derive(Clone, Debug)]
+pub struct ClassicVoice {
+    pub lub_hz: f32,
+    pub dub_hz: f32,
+    pub dub_level: f32,
+}
+
+impl Default for ClassicVoice {
+    fn default() -> Self {
+        Self { lub_hz: 45.0, dub_hz: 55.0, dub_level: 0.75 }
+    }
+}
+
+/// Thumps modelled on a chest recording: a short noise click, a tone whose
+/// pitch falls from `pitch_drop` times its rest pitch, and a body resonance.
+#[derive(Clone, Debug)]
+pub struct ParametricVoice {
+    pub pitch_hz: f32,
+    pub pitch_drop: f32,
+    pub decay_ms: f32,
+    pub click: f32,
+    pub body_hz: f32,
+    pub body_q: f32,
+    pub dub_level: f32,
+}
+
+impl Default for ParametricVoice {
+    fn default() -> Self {
+        Self {
+            pitch_hz: 42.0,
+            pitch_drop: 1.5,
+            decay_ms: 70.0,
+            click: 0.25,
+            body_hz: 110.0,
+            body_q: 3.0,
+            dub_level: 0.7,
+        }
+    }
+}
+
+/// Recorded lub and dub sounds, placed where the synthesized ones would be.
+#[derive(Clone, Debug)]
+pub struct SampledVoice {
+    pub lub: PathBuf,
+    pub dub: PathBuf,
+    pub dub_level: f32,
+}
+
+impl Default for SampledVoice {
+    fn default() -> Self {
+        Self {
+            lub: PathBuf::from("assets/heart/lub.wav"),
+            dub: PathBuf::from("assets/heart/dub.wav"),
+            dub_level: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum HeartVoice {
+    Classic(ClassicVoice),
+    Parametric(ParametricVoice),
+    Samples(SampledVoice),
+}
+
+impl Default for HeartVoice {
+    fn default() -> Self {
+        HeartVoice::Classic(ClassicVoice::default())
+    }
+}
+
+/// Beat-to-beat variation of a resting heart. Every part averages out to zero,
+/// so the beats still follow the recorded BPM curve.
+#[derive(Clone, Debug)]
+pub struct Variability {
+    pub enabled: bool,
+    /// Size of the slow wandering of the beat interval
+    pub hrv_ms: f32,
+    /// Respiratory sinus arrhythmia: the interval shortens breathing in and
+    /// lengthens breathing out by this much
+    pub rsa_ms: f32,
+    pub breaths_per_min: f32,
+    /// Independent variation of every single beat
+    pub jitter_ms: f32,
+    pub seed: u64,
+}
+
+impl Default for Variability {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hrv_ms: 25.0,
+            rsa_ms: 30.0,
+            breaths_per_min: 15.0,
+            jitter_ms: 5.0,
+            seed: 1,
+        }
     }
+}
+
+// How much of the slow HRV component carries over from one beat to the next
+const HRV_MEMORY: f32 = 0.9;
+
+/// Turns the BPM of the curve into the length of each actual beat.
+struct BeatScheduler {
+    settings: Variability,
+    rng: Rng,
+    wander: f32,
+}
+
+impl BeatScheduler {
+    fn new(settings: Variability) -> Self {
+        Self { rng: Rng::new(settings.seed), settings, wander: 0.0 }
+    }
+
+    /// Uniform noise scaled to unit standard deviation.
+    fn noise(&mut self) -> f32 {
+        (self.rng.next_f32() * 2.0 - 1.0) * 3f32.sqrt()
+    }
+
+    /// BPM to render the beat starting at `time_sec` with.
+    fn next(&mut self, curve_bpm: f32, time_sec: f64) -> f32 {
+        if !self.settings.enabled {
+            return curve_bpm;
+        }
+        let interval = 60.0 / curve_bpm;
+
+        // AR(1) keeps the wander's spread at `hrv_ms` whatever the memory
+        let innovation = (1.0 - HRV_MEMORY * HRV_MEMORY).sqrt() * self.noise();
+        self.wander = HRV_MEMORY * self.wander + innovation;
+        let jitter = self.noise();
+        let s = &self.settings;
+        let breath = (2.0 * std::f64::consts::PI * time_sec * s.breaths_per_min as f64 / 60.0).sin() as f32;
+        let offset_ms = s.hrv_ms * self.wander - s.rsa_ms * breath + s.jitter_ms * jitter;
 
-    // 2. Generate S2 (Dub)
-    let dub_start = lub_samples + gap_samples;
-    for i in 0..dub_samples {
-        let idx = dub_start + i;
-        if idx >= num_samples { break; }
-        let t = i as f32 / sample_rate as f32;
-        let env = (std::f32::consts::PI * i as f32 / dub_samples as f32).sin();
-        let wave = (2.0 * std::f32::consts::PI * dub_freq * t).sin();
-        beat[idx] = (wave * env * 0.75 * amp_mult).clamp(-1.0, 1.0);
+        // Never stray so far that a reading turns into a different rhythm
+        let varied = (interval + offset_ms / 1000.0).clamp(interval * 0.8, interval * 1.2);
+        60.0 / varied
     }
+}
+
+// Click time constant and the time constant of the pitch drop
+const CLICK_SECS: f32 = 0.004;
+const PITCH_DROP_SECS: f32 = 0.015;
+// The dub of the parametric voice is higher and shorter than the lub
+const DUB_PITCH: f32 = 1.25;
+const DUB_DECAY: f32 = 0.8;
 
-    beat
+/// Renders beat cycles in the selected voice.
+struct Heart {
+    voice: HeartVoice,
+    sample_rate: u32,
+    /// Lub and dub recordings, mono, for the sampled voice
+    recordings: Option<(Vec<f32>, Vec<f32>)>,
+    rng: Rng,
+}
+
+impl Heart {
+    fn new(voice: HeartVoice, sample_rate: u32) -> Result<Self, String> {
+        let recordings = match &voice {
+            HeartVoice::Samples(v) => {
+                let lub = audio::load_clip(&v.lub, sample_rate)?.mono();
+                let dub = audio::load_clip(&v.dub, sample_rate)?.mono();
+                Some((lub, dub))
+            }
+            _ => None,
+        };
+        Ok(Self { voice, sample_rate, recordings, rng: Rng::new(1) })
+    }
+
+    /// One beat cycle for the instantaneous BPM: the lub at the start, the dub
+    /// after it, silence up to the next beat.
+    fn beat(&mut self, current_bpm: f32) -> Vec<f32> {
+        let sr = self.sample_rate as f32;
+        let beat_len_sec = 60.0 / current_bpm;
+        let num_samples = (beat_len_sec * sr) as usize;
+        let mut beat = vec![0.0; num_samples];
+
+        let lub_len_sec = 0.1_f32.min(beat_len_sec * 0.3);
+        let gap_len_sec = 0.05_f32.min(beat_len_sec * 0.1);
+        let dub_len_sec = 0.08_f32.min(beat_len_sec * 0.25);
+
+        let lub_samples = (lub_len_sec * sr) as usize;
+        let gap_samples = (gap_len_sec * sr) as usize;
+        let dub_samples = (dub_len_sec * sr) as usize;
+        let dub_start = lub_samples + gap_samples;
+
+        match self.voice.clone() {
+            HeartVoice::Classic(v) => {
+                // 1. Generate S1 (Lub)
+                for (i, slot) in beat.iter_mut().enumerate().take(lub_samples) {
+                    let t = i as f32 / sr;
+                    let env = (std::f32::consts::PI * i as f32 / lub_samples as f32).sin();
+                    let wave = (2.0 * std::f32::consts::PI * v.lub_hz * t).sin();
+                    *slot = wave * env;
+                }
+
+                // 2. Generate S2 (Dub)
+                for i in 0..dub_samples {
+                    let idx = dub_start + i;
+                    if idx >= num_samples { break; }
+                    let t = i as f32 / sr;
+                    let env = (std::f32::consts::PI * i as f32 / dub_samples as f32).sin();
+                    let wave = (2.0 * std::f32::consts::PI * v.dub_hz * t).sin();
+                    beat[idx] = wave * env * v.dub_level;
+                }
+            }
+            HeartVoice::Parametric(v) => {
+                // Each sound may ring until the next one starts
+                let lub = self.thump(&v, 1.0, 1.0, dub_start);
+                let dub = self.thump(&v, DUB_PITCH, DUB_DECAY, num_samples.saturating_sub(dub_start));
+                mix_into(&mut beat, 0, &lub, 1.0);
+                mix_into(&mut beat, dub_start, &dub, v.dub_level);
+            }
+            HeartVoice::Samples(v) => {
+                if let Some((lub, dub)) = &self.recordings {
+                    mix_into(&mut beat, 0, lub, 1.0);
+                    mix_into(&mut beat, dub_start, dub, v.dub_level);
+                }
+                // Recordings longer than a fast beat are cut, so fade the cut
+                let fade = ((0.005 * sr) as usize).min(num_samples);
+                for (k, s) in beat.iter_mut().rev().take(fade).enumerate() {
+                    *s *= k as f32 / fade as f32;
+                }
+            }
+        }
+
+        beat
+    }
+
+    /// A single parametric sound of at most `max_len` samples.
+    fn thump(&mut self, v: &ParametricVoice, pitch: f32, decay: f32, max_len: usize) -> Vec<f32> {
+        let sr = self.sample_rate as f32;
+        let decay_secs = (v.decay_ms / 1000.0 * decay).max(0.005);
+        // Ring out to -60 dB
+        let len = ((decay_secs * 6.9 * sr) as usize).min(max_len);
+        let mut body = Svf::new(v.body_hz * pitch, v.body_q, self.sample_rate);
+        let mut phase = 0.0f32;
+        let mut out = Vec::with_capacity(len);
+
+        for i in 0..len {
+            let t = i as f32 / sr;
+            let freq = v.pitch_hz * pitch * (1.0 + (v.pitch_drop - 1.0).max(0.0) * (-t / PITCH_DROP_SECS).exp());
+            phase += 2.0 * std::f32::consts::PI * freq / sr;
+            let attack = (t / 0.003).min(1.0);
+            let tone = phase.sin() * attack * (-t / decay_secs).exp();
+            let click = (self.rng.next_f32() * 2.0 - 1.0) * v.click * (-t / CLICK_SECS).exp();
+            let excitation = tone + click;
+            out.push(0.7 * excitation + 0.5 * body.process(excitation).band);
+        }
+        out
+    }
+}
+
+fn mix_into(beat: &mut [f32], start: usize, sound: &[f32], gain: f32) {
+    for (slot, s) in beat.iter_mut().skip(start).zip(sound) {
+        *slot += s * gain;
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BpmOptions {
+    pub interpolation: Interpolation,
+    pub voice: HeartVoice,
+    pub variability: Variability,
+    /// Also writes the beats as a Standard MIDI File, `{name}.mid`
+    pub midi: bool,
+    pub video: BpmVideo,
+}
+
+/// A synthesized heartbeat file, ready to encode or mix.
+pub struct BpmRender {
+    /// Mono
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub duration_secs: f64,
+    bpm_list: Vec<f32>,
+    /// Start of every beat in seconds
+    beat_times: Vec<f64>,
+    /// Held for as long as the samples are
+    _memory: MemoryLease,
+}
+
+/// Where a BPM table sits in real time: the markers are times of day and the
+/// records carry no date, so the start counts from midnight of an unknown day.
+fn real_span(data: &BpmSeries) -> (f64, DataSpan) {
+    (data.origin_secs, DataSpan { readings: data.readings.len(), days: 1, real_secs: data.span_secs() })
+}
+
+/// Start and extent of a BPM PDF's records, for lining tracks up in a mix.
+pub fn data_span(pdf: &Path) -> Result<(f64, DataSpan), String> {
+    Ok(real_span(&BpmSeries::parse(&shared::extract_text(pdf)?)))
+}
+
+/// Parses one BPM PDF, writes its `_report.txt` and synthesizes the heartbeat
+/// at the loudness target.
+pub fn render_bpm(
+    pdf: &Path,
+    options: &BpmOptions,
+    timeline: Timeline,
+    out_dir: &Path,
+    name: &str,
+    prog_tx: &ProgressTx,
+    cancel: CancelFlag,
+) -> Result<BpmRender, String> {
+    let text = shared::extract_text(pdf)?;
+    
+    let data = BpmSeries::parse(&text);
+    let report = out_dir.join(format!("{name}_report.txt"));
+    fs::write(&report, data.report(name)).map_err(|e| format!("write {}: {e}", report.display()))?;
+    if !data.rejected.is_empty() {
+        let _ = prog_tx.send(super::Progress::Warning {
+            name: name.to_string(),
+            message: format!("{} rejected tokens in the BPM data, see the _report.txt", data.rejected.len()),
+        });
+    }
+
+    let bpm_list = data.values();
+    if bpm_list.is_empty() {
+        return Err("No valid BPM data found".into());
+    }
+
+    let sample_rate = 44100u32;
+    let total_duration_secs = timeline.checked_duration_secs(real_span(&data).1, prog_tx, name);
+    let target_samples = (total_duration_secs * sample_rate as f64) as usize;
+    
+    let series = Series::new(&bpm_list, options.interpolation);
+    let mut heart = Heart::new(options.voice.clone(), sample_rate)?;
+    let mut scheduler = BeatScheduler::new(options.variability.clone());
+    let memory = MemoryLease::reserve(target_samples, &cancel)?;
+    let mut output: Vec<f32> = Vec::with_capacity(target_samples);
+    let mut beat_times: Vec<f64> = Vec::new();
+
+    // Continuous generation loop
+    while output.len() < target_samples {
+        if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+            return Err("Cancelled.".into());
+        }
+
+        // Determine exact time in the timeline
+        let current_time_sec = output.len() as f64 / sample_rate as f64;
+        
+        // The BPM holds for a whole beat, taken where the beat starts. Readings
+        // sit at their time stamps, so a gap in the table is bridged over its length.
+        let current_bpm = series.at_index(data.index_at(current_time_sec / total_duration_secs));
+
+        // Generate exactly one beat cycle based on the *current* smooth BPM
+        let beat_data = heart.beat(scheduler.next(current_bpm, current_time_sec));
+        beat_times.push(current_time_sec);
+        let room = target_samples - output.len();
+        output.extend(beat_data.into_iter().take(room));
+    }
+
+    // Levels are set here rather than per beat so the whole file lands on the
+    // loudness target without clipping the louder thumps.
+    loudness::normalize(&mut output, 1, sample_rate);
+
+    Ok(BpmRender {
+        samples: output,
+        sample_rate,
+        duration_secs: total_duration_secs,
+        bpm_list,
+        beat_times,
+        _memory: memory,
+    })
 }
 
 pub fn convert_bpm(
     file_path: &Path,
     is_folder: bool,
+    options: BpmOptions,
+    timeline: Timeline,
     tx: ProgressTx,
     cancel: CancelFlag,
 ) -> Result<(), String> {
@@ -60,29 +392,9 @@ pub fn convert_bpm(
             return Ok(());
         }
 
-        let text = shared::extract_text(pdf)?;
-        
-        // Better Parsing: Split by whitespace to separate minute markers (01, 02) from real BPMs
-        let mut bpm_list: Vec<f32> = Vec::new();
-        for token in text.split_whitespace() {
-            // Remove any non-digit characters just in case
-            let clean_token: String = token.chars().filter(|c| c.is_ascii_digit()).collect();
-            if let Ok(val) = clean_token.parse::<f32>() {
-                // Filter out minute markers and unreasonably low BPMs
-                if val >= 60.0 {
-                    bpm_list.push(val);
-                }
-            }
-        }
-
-        if bpm_list.is_empty() {
-            return Err("No valid BPM data found (>60 BPM)".into());
-        }
+        let render = render_bpm(pdf, &options, timeline, out_dir, name, prog_tx, cancel.clone())?;
+        let (output, sample_rate) = (&render.samples, render.sample_rate);
 
-        let sample_rate = 44100u32;
-        let total_duration_secs = 12.0 * 60.0;
-        let target_samples = (total_duration_secs * sample_rate as f64) as usize;
-        
         let tmp_dir = shared::make_temp_dir("bpm")?;
         let tmp = tmp_dir.join(format!("{name}_tmp.wav"));
         {
@@ -95,40 +407,9 @@ pub fn convert_bpm(
             let mut w = hound::WavWriter::create(&tmp, spec)
                 .map_err(|e| format!("create {}: {e}", tmp.display()))?;
 
-            let mut total_written = 0usize;
-            let n_bpms = bpm_list.len();
-            
-            // Continuous generation loop
-            while total_written < target_samples {
-                if cancel.load(std::sync::atomic::Ordering::Relaxed) {
-                    return Err("Cancelled.".into());
-                }
-
-                // Determine exact time in the timeline
-                let current_time_sec = total_written as f64 / sample_rate as f64;
-                
-                // Map current time to the fractional index of our BPM array
-                let progress = current_time_sec / total_duration_secs;
-                let exact_index = progress * (n_bpms.saturating_sub(1) as f64);
-                
-                let idx1 = exact_index.floor() as usize;
-                let idx2 = (idx1 + 1).min(n_bpms - 1);
-                let frac = exact_index - idx1 as f64;
-
-                // Smooth linear interpolation between the two BPM points
-                let current_bpm = bpm_list[idx1] + (bpm_list[idx2] - bpm_list[idx1]) * frac as f32;
-
-                // Generate exactly one beat cycle based on the *current* smooth BPM
-                let beat_data = generate_single_beat(current_bpm, sample_rate);
-
-                for &sample in &beat_data {
-                    if total_written >= target_samples { break; }
-                    
-                    w.write_sample(sample).map_err(|e| e.to_string())?; // Left
-                    w.write_sample(sample).map_err(|e| e.to_string())?; // Right
-                    
-                    total_written += 1;
-                }
+            for &sample in output {
+                w.write_sample(sample).map_err(|e| e.to_string())?; // Left
+                w.write_sample(sample).map_err(|e| e.to_string())?; // Right
             }
 
             w.finalize().map_err(|e| e.to_string())?;
@@ -146,15 +427,42 @@ pub fn convert_bpm(
             partial_out.to_string_lossy().to_string(),
         ];
         
-        let result = shared::run_ffmpeg(&args, None, prog_tx, name, cancel.clone());
+        let mut result = shared::run_ffmpeg(&args, None, prog_tx, name, cancel.clone());
+
+        // The video gets the same WAV, so picture and sound share one clock
+        let video_out = out.with_extension("mp4");
+        let partial_video = out.with_extension("tmp.mp4");
+        let mut video_preview = None;
+        if result.is_ok() && options.video != BpmVideo::Off {
+            let video = BeatVideo { style: options.video, beats: &render.beat_times, duration_secs: render.duration_secs };
+            match video.encode(&tmp, &partial_video, prog_tx, name, cancel.clone()) {
+                Ok(preview) => video_preview = Some(preview),
+                Err(e) => result = Err(e),
+            }
+        }
 
         let _ = fs::remove_file(&tmp);
         let _ = fs::remove_dir_all(&tmp_dir);
         
         if result.is_ok() && !cancel.load(std::sync::atomic::Ordering::Relaxed) {
+            // Both outputs are in place before the optional extras, which only warn
             let _ = fs::rename(&partial_out, &out);
+            if let Some(preview) = video_preview {
+                let _ = fs::rename(&partial_video, &video_out);
+                preview.write(&video_out, prog_tx, name);
+            }
+            let graph = out_dir.join(format!("{name}_waveform.png"));
+            if let Err(e) = preview::write_waveform(&graph, output, 1, &render.bpm_list) {
+                let _ = prog_tx.send(super::Progress::Warning { name: name.to_string(), message: format!("waveform image: {e}") });
+            }
+            if options.midi {
+                if let Err(e) = midi::write_beats(&out_dir.join(format!("{name}.mid")), &render.beat_times, render.duration_secs) {
+                    let _ = prog_tx.send(super::Progress::Warning { name: name.to_string(), message: format!("MIDI export: {e}") });
+                }
+            }
         } else {
             let _ = fs::remove_file(&partial_out);
+            let _ = fs::remove_file(&partial_video);
         }
 
         result
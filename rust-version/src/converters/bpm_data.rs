@@ -0,0 +1,300 @@
+/// Heart rates outside this range are misreadings rather than a heart.
+const MIN_BPM: f32 = 25.0;
+const MAX_BPM: f32 = 250.0;
+
+/// Token centres closer than this many characters belong to the same column.
+const COLUMN_GAP: f32 = 2.5;
+
+/// Row length assumed when the markers do not reveal it, one minute.
+const DEFAULT_ROW_SECS: f64 = 60.0;
+
+#[derive(Clone, Copy, Debug)]
+pub struct BpmReading {
+    /// Seconds from the first row marker
+    pub time_secs: f64,
+    pub bpm: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct RejectedToken {
+    /// Line of the extracted text, 1-based
+    pub line: usize,
+    pub token: String,
+    pub reason: &'static str,
+}
+
+/// Readings from a BPM table laid out as one row per time marker, e.g.
+/// `01   135  140  135  140  120  145`, with the columns evenly splitting the
+/// time until the next row.
+#[derive(Clone, Debug, Default)]
+pub struct BpmSeries {
+    pub readings: Vec<BpmReading>,
+    pub rejected: Vec<RejectedToken>,
+    /// Time covered by one row
+    pub row_secs: f64,
+    pub columns: usize,
+    /// Time of day of the first row marker; reading times count from it
+    pub origin_secs: f64,
+}
+
+struct Token<'a> {
+    /// Character column of the first and one past the last character
+    start: usize,
+    end: usize,
+    text: &'a str,
+}
+
+impl Token<'_> {
+    fn centre(&self) -> f32 {
+        (self.start + self.end) as f32 / 2.0
+    }
+}
+
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut byte_start = 0;
+    let mut column = 0;
+    for (byte, c) in line.char_indices() {
+        if c.is_whitespace() {
+            if let Some(s) = start.take() {
+                tokens.push(Token { start: s, end: column, text: &line[byte_start..byte] });
+            }
+        } else if start.is_none() {
+            start = Some(column);
+            byte_start = byte;
+        }
+        column += 1;
+    }
+    if let Some(s) = start {
+        tokens.push(Token { start: s, end: column, text: &line[byte_start..] });
+    }
+    tokens
+}
+
+/// Seconds of a row marker: `01` or `1.` in minutes, `1:05` as hours and
+/// minutes, `1:05:30` with seconds.
+fn parse_marker(token: &str) -> Option<f64> {
+    let token = token.trim_end_matches(['.', ':']);
+    let parts: Vec<&str> = token.split(':').collect();
+    if parts.iter().any(|p| p.is_empty() || p.len() > 3 || !p.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    let n: Vec<f64> = parts.iter().map(|p| p.parse::<f64>().unwrap_or(0.0)).collect();
+    match n.as_slice() {
+        [m] => Some(m * 60.0),
+        [h, m] if *m < 60.0 => Some(h * 3600.0 + m * 60.0),
+        [h, m, s] if *m < 60.0 && *s < 60.0 => Some(h * 3600.0 + m * 60.0 + s),
+        _ => None,
+    }
+}
+
+fn in_range(bpm: f32) -> bool {
+    (MIN_BPM..=MAX_BPM).contains(&bpm)
+}
+
+/// Whether a row's first token, read as a marker at `time`, really is one.
+/// Punctuated or zero-padded markers and ones too low for a heart rate always
+/// are; a bare `120` could be a reading that lost its marker, so it has to
+/// follow the earlier markers by no more than two of their steps.
+fn is_marker(token: &str, time: f64, row_times: &[f64]) -> bool {
+    let could_be_reading = !token.contains(['.', ':']) && !token.starts_with('0') && token.parse::<f32>().is_ok_and(in_range);
+    if !could_be_reading {
+        return true;
+    }
+    let step = match row_times {
+        [.., before, last] => (last - before).max(DEFAULT_ROW_SECS),
+        _ => DEFAULT_ROW_SECS,
+    };
+    row_times.last().is_some_and(|last| time > *last && time - last <= 2.0 * step)
+}
+
+/// Splits digits that lost the space between them, like `135140`, into
+/// readings of two or three digits that are all plausible heart rates.
+fn split_merged(digits: &str) -> Option<Vec<(usize, f32)>> {
+    fn solve(digits: &str, offset: usize) -> Option<Vec<(usize, f32)>> {
+        if digits.is_empty() {
+            return Some(Vec::new());
+        }
+        for len in [3, 2] {
+            if digits.len() < len || digits.starts_with('0') {
+                continue;
+            }
+            let bpm = digits[..len].parse::<f32>().ok().filter(|v| in_range(*v));
+            if let Some(bpm) = bpm {
+                if let Some(mut rest) = solve(&digits[len..], offset + len) {
+                    rest.insert(0, (offset, bpm));
+                    return Some(rest);
+                }
+            }
+        }
+        None
+    }
+    solve(digits, 0).filter(|parts| parts.len() > 1)
+}
+
+/// A reading cell placed at its character centre.
+struct Cell {
+    line: usize,
+    row_time: f64,
+    centre: f32,
+    order: usize,
+    bpm: f32,
+}
+
+impl BpmSeries {
+    pub fn parse(text: &str) -> Self {
+        let mut series = BpmSeries::default();
+        let mut cells: Vec<Cell> = Vec::new();
+        let mut row_times: Vec<f64> = Vec::new();
+        let mut widest_row = 0;
+
+        for (index, line) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let tokens = tokenize(line);
+            let Some((first, rest)) = tokens.split_first() else { continue };
+            let row_time = match parse_marker(first.text) {
+                Some(t) if !rest.is_empty() && is_marker(first.text, t, &row_times) => t,
+                _ => {
+                    // Headings and page numbers are fine, a heart rate outside a
+                    // timed row is a reading that lost its marker
+                    for t in tokens.iter().filter(|t| t.text.parse::<f32>().is_ok_and(in_range)) {
+                        series.rejected.push(RejectedToken {
+                            line: line_no,
+                            token: t.text.to_string(),
+                            reason: "reading outside a timed row",
+                        });
+                    }
+                    continue;
+                }
+            };
+            row_times.push(row_time);
+
+            let mut order = 0;
+            for token in rest {
+                let mut reject = |reason| {
+                    series.rejected.push(RejectedToken { line: line_no, token: token.text.to_string(), reason });
+                };
+                let digits = token.text.chars().all(|c| c.is_ascii_digit());
+                match token.text.parse::<f32>() {
+                    Ok(bpm) if in_range(bpm) => {
+                        cells.push(Cell { line: line_no, row_time, centre: token.centre(), order, bpm });
+                        order += 1;
+                    }
+                    Ok(_) if digits && token.text.len() >= 4 => match split_merged(token.text) {
+                        Some(parts) => {
+                            for (offset, bpm) in parts {
+                                // Each part sits where it would have been printed
+                                let centre = (token.start + offset) as f32 + 1.0;
+                                cells.push(Cell { line: line_no, row_time, centre, order, bpm });
+                                order += 1;
+                            }
+                        }
+                        None => reject("merged digits that split into no heart rates"),
+                    },
+                    Ok(bpm) if bpm.is_finite() => reject("outside 25-250 BPM"),
+                    _ => reject("not a number"),
+                }
+            }
+            widest_row = widest_row.max(order);
+        }
+
+        if cells.is_empty() {
+            return series;
+        }
+
+        // Cluster the cell centres into the table's columns
+        let mut centres: Vec<f32> = cells.iter().map(|c| c.centre).collect();
+        centres.sort_by(f32::total_cmp);
+        let mut columns: Vec<(f32, usize)> = Vec::new();
+        for centre in centres {
+            match columns.last_mut() {
+                Some((sum, count)) if centre - *sum / *count as f32 <= COLUMN_GAP => {
+                    *sum += centre;
+                    *count += 1;
+                }
+                _ => columns.push((centre, 1)),
+            }
+        }
+        let column_centres: Vec<f32> = columns.iter().map(|(sum, count)| sum / *count as f32).collect();
+
+        // Without aligned columns (e.g. text from the fallback extractor) the
+        // clusters are meaningless and cells go by their order in the row
+        let aligned = column_centres.len() <= widest_row * 2;
+        series.columns = if aligned { column_centres.len() } else { widest_row }.max(1);
+
+        let mut gaps: Vec<f64> = row_times.windows(2).map(|w| w[1] - w[0]).filter(|g| *g > 0.0).collect();
+        gaps.sort_by(f64::total_cmp);
+        series.row_secs = gaps.get(gaps.len() / 2).copied().unwrap_or(DEFAULT_ROW_SECS);
+
+        let origin = row_times.iter().cloned().fold(f64::INFINITY, f64::min);
+        series.origin_secs = if origin.is_finite() { origin } else { 0.0 };
+        let mut timed: Vec<(f64, &Cell)> = Vec::with_capacity(cells.len());
+        for cell in &cells {
+            let column = if aligned {
+                column_centres
+                    .iter()
+                    .enumerate()
+                    .min_by(|a, b| (a.1 - cell.centre).abs().total_cmp(&(b.1 - cell.centre).abs()))
+                    .map_or(0, |(i, _)| i)
+            } else {
+                cell.order
+            };
+            timed.push((cell.row_time - origin + column as f64 * series.row_secs / series.columns as f64, cell));
+        }
+        // Stable, so of the cells sharing a time stamp the first printed is kept
+        timed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (time_secs, cell) in timed {
+            // A repeated row marker or a misaligned column puts two cells on one time stamp
+            if series.readings.last().is_some_and(|r| (time_secs - r.time_secs).abs() < 1e-6) {
+                series.rejected.push(RejectedToken { line: cell.line, token: cell.bpm.to_string(), reason: "duplicate time stamp" });
+                continue;
+            }
+            series.readings.push(BpmReading { time_secs, bpm: cell.bpm });
+        }
+        series.rejected.sort_by_key(|r| r.line);
+        series
+    }
+
+    pub fn values(&self) -> Vec<f32> {
+        self.readings.iter().map(|r| r.bpm).collect()
+    }
+
+    /// Real time from the first reading to the end of the last one's cell.
+    pub fn span_secs(&self) -> f64 {
+        let cell = self.row_secs / self.columns.max(1) as f64;
+        self.readings.last().map_or(0.0, |r| r.time_secs + cell)
+    }
+
+    /// Fractional reading index at `progress` through the covered time, so
+    /// gaps in the table stretch between the readings around them.
+    pub fn index_at(&self, progress: f64) -> f64 {
+        let n = self.readings.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let first = self.readings[0].time_secs;
+        let last = self.readings[n - 1].time_secs;
+        let t = first + progress.clamp(0.0, 1.0) * (last - first);
+        let i = self.readings.partition_point(|r| r.time_secs <= t).clamp(1, n - 1) - 1;
+        let (a, b) = (self.readings[i].time_secs, self.readings[i + 1].time_secs);
+        i as f64 + if b > a { ((t - a) / (b - a)).clamp(0.0, 1.0) } else { 0.0 }
+    }
+
+    pub fn report(&self, source: &str) -> String {
+        let mut out = format!(
+            "BPM data report for {source}\n{} readings in {} columns, {:.0} s per row, {} rejected tokens\n",
+            self.readings.len(),
+            self.columns,
+            self.row_secs,
+            self.rejected.len()
+        );
+        if !self.rejected.is_empty() {
+            out.push_str("\nRejected:\n");
+            for r in &self.rejected {
+                out.push_str(&format!("  line {:>4}  {:<12}  {}\n", r.line, r.token, r.reason));
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,229 @@
+use std::io::Write;
+use std::path::Path;
+
+use super::preview::PreviewCapture;
+use super::{shared, CancelFlag, ProgressTx};
+
+const SIZE: usize = 520;
+const FPS: f64 = 24.0;
+
+const BACKGROUND: [f32; 3] = [8.0, 6.0, 8.0];
+const HEART: [f32; 3] = [214.0, 32.0, 52.0];
+const TRACE: [f32; 3] = [60.0, 255.0, 130.0];
+const GRID: [f32; 3] = [14.0, 42.0, 24.0];
+
+/// Heart size at rest as a share of the frame, and how much a beat swells it.
+const HEART_SCALE: f32 = 0.26;
+const HEART_SWELL: f32 = 0.16;
+/// Angles the heart outline is resolved at.
+const OUTLINE_STEPS: usize = 2048;
+
+/// Seconds of trace across the frame.
+const TRACE_WINDOW: f64 = 4.0;
+/// ECG paper squares, in pixels.
+const GRID_STEP: usize = 26;
+
+/// Picture that goes with the heartbeat audio.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BpmVideo {
+    /// Audio only
+    #[default]
+    Off,
+    /// A heart that swells on every beat
+    Pulse,
+    /// A scrolling monitor trace with an R peak on every beat
+    Ecg,
+}
+
+/// Size of a beat, 0 at rest and 1 at its peak: a 30 ms rise on the beat's
+/// first sample and an exponential fall.
+fn beat_envelope(since: f64) -> f32 {
+    const ATTACK: f64 = 0.03;
+    if since < 0.0 {
+        0.0
+    } else if since < ATTACK {
+        (since / ATTACK) as f32
+    } else {
+        (-(since - ATTACK) / 0.15).exp() as f32
+    }
+}
+
+/// Idealised ECG complex around an R peak at `d` = 0 seconds.
+fn ecg_complex(d: f64) -> f32 {
+    let g = |centre: f64, width: f64| (-(d - centre).powi(2) / (2.0 * width * width)).exp();
+    (0.15 * g(-0.16, 0.025) - 0.12 * g(-0.025, 0.008) + g(0.0, 0.01) - 0.25 * g(0.03, 0.01) + 0.3 * g(0.25, 0.04)) as f32
+}
+
+fn shade(into: &mut [u8], color: [f32; 3], amount: f32) {
+    for (c, (bg, fg)) in into.iter_mut().zip(BACKGROUND.iter().zip(color)) {
+        *c = (bg + (fg - bg) * amount.clamp(0.0, 1.0)) as u8;
+    }
+}
+
+/// Outline radius of the heart `(x²+y²-1)³ = x²y³` per angle, found by walking
+/// out from its centre to the first point outside it.
+fn heart_outline() -> Vec<f32> {
+    let inside = |x: f32, y: f32| (x * x + y * y - 1.0).powi(3) - x * x * y * y * y <= 0.0;
+    (0..OUTLINE_STEPS)
+        .map(|i| {
+            let angle = i as f32 / OUTLINE_STEPS as f32 * std::f32::consts::TAU;
+            let (dx, dy) = (angle.cos(), angle.sin());
+            let mut r = 0.0;
+            while r < 2.0 && inside(r * dx, r * dy) {
+                r += 0.01;
+            }
+            let (mut lo, mut hi) = (r - 0.01, r);
+            for _ in 0..12 {
+                let mid = (lo + hi) / 2.0;
+                if inside(mid * dx, mid * dy) { lo = mid } else { hi = mid }
+            }
+            lo
+        })
+        .collect()
+}
+
+struct Renderer<'a> {
+    style: BpmVideo,
+    beats: &'a [f64],
+    /// Per pixel, the heart scale at which its edge reaches that pixel
+    reach: Vec<f32>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(style: BpmVideo, beats: &'a [f64]) -> Self {
+        let mut reach = Vec::new();
+        if style == BpmVideo::Pulse {
+            let outline = heart_outline();
+            let unit = SIZE as f32;
+            reach.reserve(SIZE * SIZE);
+            for py in 0..SIZE {
+                for px in 0..SIZE {
+                    // The shape's own centre sits a little above the middle of its outline
+                    let x = (px as f32 - SIZE as f32 / 2.0) / unit;
+                    let y = (SIZE as f32 / 2.0 - py as f32) / unit + 0.03;
+                    let angle = y.atan2(x).rem_euclid(std::f32::consts::TAU);
+                    let step = ((angle / std::f32::consts::TAU * OUTLINE_STEPS as f32) as usize) % OUTLINE_STEPS;
+                    reach.push((x * x + y * y).sqrt() / outline[step].max(1e-3));
+                }
+            }
+        }
+        Self { style, beats, reach }
+    }
+
+    /// Start of the last beat at or before `t`.
+    fn last_beat(&self, t: f64) -> Option<f64> {
+        let i = self.beats.partition_point(|b| *b <= t);
+        i.checked_sub(1).map(|i| self.beats[i])
+    }
+
+    fn ecg(&self, t: f64) -> f32 {
+        // Only the beats either side reach far enough to matter
+        let i = self.beats.partition_point(|b| *b <= t);
+        let from = i.saturating_sub(1);
+        let to = (i + 1).min(self.beats.len());
+        self.beats[from..to].iter().map(|b| ecg_complex(t - b)).sum()
+    }
+
+    fn draw(&self, t: f64, raw: &mut [u8]) {
+        match self.style {
+            BpmVideo::Pulse => {
+                let swell = self.last_beat(t).map_or(0.0, |b| beat_envelope(t - b));
+                let scale = HEART_SCALE * (1.0 + HEART_SWELL * swell);
+                // About a pixel and a half of soft edge
+                let edge = 1.5 / (SIZE as f32 * scale);
+                let glow = 0.75 + 0.25 * swell;
+                for (px, reach) in raw.chunks_mut(3).zip(&self.reach) {
+                    let cover = ((1.0 - reach / scale) / edge + 0.5).clamp(0.0, 1.0);
+                    shade(px, HEART, cover * glow);
+                }
+            }
+            BpmVideo::Ecg => {
+                for (i, px) in raw.chunks_mut(3).enumerate() {
+                    let (x, y) = (i % SIZE, i / SIZE);
+                    let on_grid = x % GRID_STEP == 0 || y % GRID_STEP == 0;
+                    shade(px, GRID, if on_grid { 1.0 } else { 0.0 });
+                }
+                let mid = SIZE as f32 * 0.6;
+                let amplitude = SIZE as f32 * 0.35;
+                let y_at = |x: usize| {
+                    let when = t - (SIZE - 1 - x) as f64 / SIZE as f64 * TRACE_WINDOW;
+                    let value = if when < 0.0 { 0.0 } else { self.ecg(when) };
+                    (mid - value * amplitude).clamp(1.0, SIZE as f32 - 2.0)
+                };
+                let mut previous = y_at(0);
+                for x in 0..SIZE {
+                    let current = y_at(x);
+                    // Older trace fades like phosphor
+                    let brightness = 0.3 + 0.7 * x as f32 / SIZE as f32;
+                    let top = previous.min(current).floor() as usize - 1;
+                    let bottom = previous.max(current).ceil() as usize + 1;
+                    for y in top..=bottom.min(SIZE - 1) {
+                        let px = &mut raw[(y * SIZE + x) * 3..][..3];
+                        shade(px, TRACE, brightness);
+                    }
+                    previous = current;
+                }
+            }
+            BpmVideo::Off => {}
+        }
+    }
+}
+
+/// The beats of one synthesized file, ready to be drawn.
+pub struct BeatVideo<'a> {
+    pub style: BpmVideo,
+    /// Start of every synthesized beat in seconds, so each pulse lands on the
+    /// frame its thump starts in
+    pub beats: &'a [f64],
+    pub duration_secs: f64,
+}
+
+impl BeatVideo<'_> {
+    /// Renders the frames and muxes them with `audio` into `partial_out`.
+    pub fn encode(
+        &self,
+        audio: &Path,
+        partial_out: &Path,
+        prog_tx: &ProgressTx,
+        name: &str,
+        cancel: CancelFlag,
+    ) -> Result<PreviewCapture, String> {
+        let num_frames = ((self.duration_secs * FPS).ceil() as usize).max(1);
+        let renderer = Renderer::new(self.style, self.beats);
+
+        let mut args: Vec<String> = vec![
+            "-y".into(), "-hide_banner".into(), "-loglevel".into(), "error".into(),
+            "-f".into(), "rawvideo".into(), "-pix_fmt".into(), "rgb24".into(),
+            "-s".into(), format!("{SIZE}x{SIZE}"), "-r".into(), format!("{FPS}"),
+            "-i".into(), "pipe:0".into(),
+            "-i".into(), audio.to_string_lossy().to_string(),
+            "-map".into(), "0:v".into(), "-map".into(), "1:a".into(),
+            "-c:v".into(), "libx264".into(), "-preset".into(), shared::ffmpeg_preset(),
+            "-pix_fmt".into(), "yuv420p".into(),
+            "-c:a".into(), "aac".into(), "-b:a".into(), "256k".into(), "-shortest".into(),
+        ];
+        args.push(partial_out.to_string_lossy().to_string());
+
+        let mut preview = PreviewCapture::new(SIZE as u32, SIZE as u32, num_frames, FPS as f32);
+        shared::run_ffmpeg_stream(&args, prog_tx, name, cancel.clone(), |stdin| {
+            let mut raw = vec![0u8; SIZE * SIZE * 3];
+            for frame in 0..num_frames {
+                if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                    return Err("Cancelled.".into());
+                }
+                renderer.draw(frame as f64 / FPS, &mut raw);
+                preview.offer(frame, &raw);
+                if stdin.write_all(&raw).is_err() { break; }
+
+                if frame % 240 == 0 {
+                    let _ = prog_tx.send(super::Progress::Update {
+                        name: name.to_string(),
+                        fraction: 0.5 + 0.5 * frame as f32 / num_frames as f32,
+                    });
+                }
+            }
+            Ok(())
+        })?;
+        Ok(preview)
+    }
+}
@@ -2,7 +2,8 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use image::{imageops};
 use std::io::Write;
-use super::{shared, CancelFlag, ProgressTx};
+use super::shared::{DataSpan, Timeline};
+use super::{preview::PreviewCapture, shared, CancelFlag, ProgressTx};
 
 fn list_images(dir: &Path) -> Result<Vec<PathBuf>, String> {
     let mut files: Vec<_> = fs::read_dir(dir)
@@ -17,10 +18,16 @@ fn list_images(dir: &Path) -> Result<Vec<PathBuf>, String> {
     Ok(files)
 }
 
+/// Cloud records are one page, or one scanned image, per day.
+fn page_span(pages: usize) -> DataSpan {
+    DataSpan { readings: pages, days: pages, real_secs: pages as f64 * 86400.0 }
+}
+
 pub fn convert_clouds(
     file_path: &Path,
     is_folder: bool,
     stitch_images: bool, 
+    timeline: Timeline,
     tx: ProgressTx,
     cancel: CancelFlag,
 ) -> Result<(), String> {
@@ -55,7 +62,7 @@ pub fn convert_clouds(
 
         images.push(image::RgbImage::new(750, 360));
 
-        let video_dur = 12.0 * 60.0;
+        let video_dur = timeline.checked_duration_secs(page_span(page_files.len()), &tx, &stem_str);
         let fps = 24.0;
         let total_frames = (video_dur * fps) as usize;
         
@@ -69,6 +76,7 @@ pub fn convert_clouds(
         ];
         
         let total_virtual_w = images.len() as f32 * 750.0;
+        let mut preview = PreviewCapture::new(750, 360, total_frames, fps as f32);
 
         let result = shared::run_ffmpeg_stream(&args, &tx, &stem_str, cancel.clone(), |stdin| {
             let mut frame = vec![0u8; 750 * 360 * 3];
@@ -106,6 +114,7 @@ pub fn convert_clouds(
                     }
                 }
 
+                preview.offer(f, &frame);
                 if stdin.write_all(&frame).is_err() { break; }
                 
                 if f % 240 == 0 {
@@ -119,8 +128,9 @@ pub fn convert_clouds(
         });
 
         if result.is_ok() && !cancel.load(std::sync::atomic::Ordering::Relaxed) {
-            let _ = tx.send(super::Progress::Done { name: stem_str });
             let _ = fs::rename(&partial_out, &out);
+            preview.write(&out, &tx, &stem_str);
+            let _ = tx.send(super::Progress::Done { name: stem_str });
         } else {
             let _ = fs::remove_file(&partial_out);
         }
@@ -167,7 +177,7 @@ pub fn convert_clouds(
 
             images.push(image::RgbImage::new(750, 360));
 
-            let video_dur = 12.0 * 60.0;
+            let video_dur = timeline.checked_duration_secs(page_span(page_files.len()), prog_tx, name);
             let fps = 24.0;
             let total_frames = (video_dur * fps) as usize;
             
@@ -186,6 +196,7 @@ pub fn convert_clouds(
             args.push(partial_out.to_string_lossy().to_string());
             
             let total_virtual_w = images.len() as f32 * 750.0;
+            let mut preview = PreviewCapture::new(750, 360, total_frames, fps as f32);
 
             let result = shared::run_ffmpeg_stream(&args, prog_tx, name, cancel.clone(), |stdin| {
                 let mut frame = vec![0u8; 750 * 360 * 3];
@@ -223,6 +234,7 @@ pub fn convert_clouds(
                         }
                     }
 
+                    preview.offer(f, &frame);
                     if stdin.write_all(&frame).is_err() { break; }
                     
                     if f % 240 == 0 {
@@ -239,6 +251,7 @@ pub fn convert_clouds(
             
             if result.is_ok() && !cancel.load(std::sync::atomic::Ordering::Relaxed) {
                 let _ = fs::rename(&partial_out, &out);
+                preview.write(&out, prog_tx, name);
             } else {
                 let _ = fs::remove_file(&partial_out);
             }
@@ -0,0 +1,172 @@
+use super::interp::{Interpolation, Series};
+
+/// Below this chroma or saturation a colour is grey and its hue means nothing.
+const GREY: f32 = 1e-3;
+
+/// Space a colour gradient is interpolated in.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorSpace {
+    /// The stored byte values, as the converter always did
+    #[default]
+    Srgb,
+    /// Physically even light mixing, brighter midpoints than sRGB
+    LinearRgb,
+    /// Perceptually even lightness and colour, no grey midpoints
+    Oklab,
+    /// OKLab as lightness, chroma and hue, turning the short way round the hue circle
+    Oklch,
+    /// Hue, saturation and value, turning the short way round the hue circle
+    Hsv,
+}
+
+impl ColorSpace {
+    /// Component holding the hue in degrees, if the space has one.
+    fn hue_index(self) -> Option<usize> {
+        match self {
+            ColorSpace::Oklch => Some(2),
+            ColorSpace::Hsv => Some(0),
+            _ => None,
+        }
+    }
+
+    /// Components of an sRGB colour in this space.
+    fn encode(self, rgb: [u8; 3]) -> [f32; 3] {
+        let srgb = rgb.map(|c| c as f32 / 255.0);
+        match self {
+            ColorSpace::Srgb => srgb.map(|c| c * 255.0),
+            ColorSpace::LinearRgb => srgb.map(to_linear),
+            ColorSpace::Oklab => oklab(srgb.map(to_linear)),
+            ColorSpace::Oklch => {
+                let [l, a, b] = oklab(srgb.map(to_linear));
+                [l, a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.0)]
+            }
+            ColorSpace::Hsv => hsv(srgb),
+        }
+    }
+
+    /// Back to sRGB bytes.
+    fn decode(self, value: [f32; 3]) -> [u8; 3] {
+        let srgb = match self {
+            ColorSpace::Srgb => value.map(|c| c / 255.0),
+            ColorSpace::LinearRgb => value.map(from_linear),
+            ColorSpace::Oklab => oklab_to_linear(value).map(from_linear),
+            ColorSpace::Oklch => {
+                let [l, c, h] = value;
+                let h = h.to_radians();
+                oklab_to_linear([l, c * h.cos(), c * h.sin()]).map(from_linear)
+            }
+            ColorSpace::Hsv => hsv_to_srgb(value),
+        };
+        // Paths through OKLab can leave the sRGB gamut, they are clipped back in
+        srgb.map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8)
+    }
+}
+
+fn to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn from_linear(c: f32) -> f32 {
+    let c = c.clamp(0.0, 1.0);
+    if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+/// Linear sRGB to OKLab, after Björn Ottosson.
+fn oklab([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+fn oklab_to_linear([l, a, b]: [f32; 3]) -> [f32; 3] {
+    let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+    let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+    let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+    [
+        4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_,
+        -1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_,
+        -0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_,
+    ]
+}
+
+/// Gamma-encoded sRGB in 0..1 to hue in degrees, saturation and value.
+fn hsv([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta <= 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max > 0.0 { delta / max } else { 0.0 };
+    [hue.rem_euclid(360.0), saturation, max]
+}
+
+fn hsv_to_srgb([h, s, v]: [f32; 3]) -> [f32; 3] {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    [r + m, g + m, b + m]
+}
+
+/// A colour series sampled continuously, each component interpolated in `space`.
+pub struct Gradient {
+    space: ColorSpace,
+    components: [Series; 3],
+}
+
+impl Gradient {
+    pub fn new(colors: &[[u8; 3]], space: ColorSpace, mode: Interpolation) -> Self {
+        let mut values: Vec<[f32; 3]> = colors.iter().map(|c| space.encode(*c)).collect();
+
+        if let Some(hue) = space.hue_index() {
+            // Chroma in OKLCh and saturation in HSV are both the middle component
+            let strength = 1;
+            // A grey takes the hue of its nearest coloured neighbour so the path
+            // towards it does not sweep through unrelated hues
+            let coloured: Vec<usize> = (0..values.len()).filter(|&i| values[i][strength] > GREY).collect();
+            if !coloured.is_empty() {
+                for i in 0..values.len() {
+                    if values[i][strength] <= GREY {
+                        let nearest = coloured.iter().min_by_key(|&&j| j.abs_diff(i)).copied().unwrap_or(i);
+                        values[i][hue] = values[nearest][hue];
+                    }
+                }
+            }
+            // Unwrap so consecutive hues never differ by more than half a turn
+            for i in 1..values.len() {
+                let step = (values[i][hue] - values[i - 1][hue] + 180.0).rem_euclid(360.0) - 180.0;
+                values[i][hue] = values[i - 1][hue] + step;
+            }
+        }
+
+        let components = [0, 1, 2].map(|c| {
+            let channel: Vec<f32> = values.iter().map(|v| v[c]).collect();
+            Series::new(&channel, mode)
+        });
+        Self { space, components }
+    }
+
+    /// Colour at `progress` in 0..1, the first entry at 0 and the last at 1.
+    pub fn at(&self, progress: f64) -> [u8; 3] {
+        self.space.decode([0, 1, 2].map(|c| self.components[c].at(progress)))
+    }
+}
@@ -0,0 +1,192 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use rayon::prelude::*;
+
+use super::bpm::{self, BpmOptions};
+use super::shared::{DataSpan, MemoryScope, Timeline};
+use super::wind::{self, SpeakerLayout, WindOptions};
+use super::{audio, loudness, preview, shared, CancelFlag, Progress, ProgressTx};
+
+/// File name of the master, written next to the first track's PDF.
+const MIX_NAME: &str = "Cube-Mix";
+
+/// What a track sonifies, with the settings of its own tab.
+#[derive(Clone, Debug)]
+pub enum TrackSource {
+    Wind(WindOptions),
+    Bpm(BpmOptions),
+}
+
+#[derive(Clone, Debug)]
+pub struct Track {
+    pub pdf: PathBuf,
+    pub source: TrackSource,
+    pub gain_db: f32,
+    /// -1 hard left, 0 centre, 1 hard right
+    pub pan: f32,
+    pub mute: bool,
+}
+
+/// Where a track's records start in real time and how much they cover.
+fn track_span(track: &Track) -> Result<(f64, DataSpan), String> {
+    match &track.source {
+        TrackSource::Wind(options) => wind::data_span(&track.pdf, options),
+        TrackSource::Bpm(_) => bpm::data_span(&track.pdf),
+    }
+}
+
+/// Synthesizes one track as interleaved stereo at the loudness target.
+fn render_track(track: &Track, timeline: Timeline, out_dir: &Path, prog_tx: &ProgressTx, cancel: CancelFlag) -> Result<Vec<f32>, String> {
+    let name = track.pdf.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    match &track.source {
+        TrackSource::Wind(options) => {
+            // The master is stereo, so directions pan within it
+            let options = WindOptions { layout: SpeakerLayout::Stereo, ..options.clone() };
+            let render = wind::render_wind(&track.pdf, &options, timeline, out_dir, &name, prog_tx, cancel)?;
+            Ok(match render.channels {
+                1 => render.samples.iter().flat_map(|s| [*s, *s]).collect(),
+                _ => render.samples,
+            })
+        }
+        TrackSource::Bpm(options) => {
+            let render = bpm::render_bpm(&track.pdf, options, timeline, out_dir, &name, prog_tx, cancel)?;
+            Ok(render.samples.iter().flat_map(|s| [*s, *s]).collect())
+        }
+    }
+}
+
+/// Renders every unmuted track against one timeline spanning all their records,
+/// so one playback second covers the same stretch of recorded time in each and
+/// every track starts where its records start, and encodes their sum as one MP3.
+/// BPM records have no date, so BPM tracks are placed on the first wind day.
+/// Tracks render one after another under a single share of the memory budget.
+pub fn convert_mix(tracks: &[Track], timeline: Timeline, tx: ProgressTx, cancel: CancelFlag) -> Result<(), String> {
+    let live: Vec<&Track> = tracks.iter().filter(|t| !t.mute).collect();
+    let first = live.first().ok_or("Every track is muted")?;
+    let out_dir = first.pdf.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+    let out = out_dir.join(format!("{MIX_NAME}.mp3"));
+    let partial_out = out.with_extension("tmp.mp3");
+
+    let _ = tx.send(Progress::Init { total: 1 });
+    let _ = tx.send(Progress::Start { name: MIX_NAME.to_string() });
+
+    let result = mix(&live, timeline, &out_dir, &out, &partial_out, &tx, cancel.clone());
+    match result {
+        Ok(()) => {
+            let _ = tx.send(Progress::Done { name: MIX_NAME.to_string() });
+        }
+        Err(e) if cancel.load(Ordering::Relaxed) || e == "Cancelled." => {}
+        Err(error) => {
+            let _ = tx.send(Progress::Error { name: MIX_NAME.to_string(), error });
+        }
+    }
+
+    if cancel.load(Ordering::Relaxed) {
+        Err("Cancelled.".into())
+    } else {
+        Ok(())
+    }
+}
+
+fn mix(
+    tracks: &[&Track],
+    timeline: Timeline,
+    out_dir: &Path,
+    out: &Path,
+    partial_out: &Path,
+    tx: &ProgressTx,
+    cancel: CancelFlag,
+) -> Result<(), String> {
+    let sample_rate = 44100u32;
+    let mut spans: Vec<(f64, DataSpan)> = tracks.par_iter().map(|track| track_span(track)).collect::<Result<_, _>>()?;
+    // BPM records carry no date, next to wind days they are taken to fall on the first one
+    let wind_day = tracks
+        .iter()
+        .zip(&spans)
+        .filter(|(track, _)| matches!(track.source, TrackSource::Wind(_)))
+        .map(|(_, (s, _))| (s / 86400.0).floor() * 86400.0)
+        .reduce(f64::min);
+    if let Some(wind_day) = wind_day {
+        for (track, (s, _)) in tracks.iter().zip(spans.iter_mut()) {
+            if matches!(track.source, TrackSource::Bpm(_)) {
+                *s += wind_day;
+                let _ = tx.send(Progress::Warning {
+                    name: MIX_NAME.to_string(),
+                    message: format!(
+                        "{} has no date, placed on day {} of the wind records",
+                        track.pdf.file_stem().unwrap_or_default().to_string_lossy(),
+                        (wind_day / 86400.0) as u32 + 1
+                    ),
+                });
+            }
+        }
+    }
+    let start = spans.iter().map(|(s, _)| *s).fold(f64::INFINITY, f64::min);
+    let end = spans.iter().map(|(s, span)| s + span.real_secs).fold(f64::NEG_INFINITY, f64::max);
+    let union = (end - start).max(1.0);
+    let whole = DataSpan {
+        // As many readings as the densest track would have over the whole span
+        readings: spans.iter().map(|(_, s)| (s.readings as f64 * union / s.real_secs.max(1.0)).round() as usize).max().unwrap_or(0),
+        days: (union / 86400.0).ceil() as usize,
+        real_secs: union,
+    };
+    let duration = timeline.checked_duration_secs(whole, tx, MIX_NAME);
+    // Playback seconds per second of records, the same for every track
+    let rate = duration / union;
+
+    // Tracks render one at a time into the master, so the most held at once is
+    // the master, the largest track and its stereo copy
+    let master_samples = (duration * sample_rate as f64) as usize * 2;
+    let largest = spans.iter().map(|(_, span)| (span.real_secs * rate * sample_rate as f64) as usize * 2).max().unwrap_or(0);
+    let _memory = MemoryScope::reserve(master_samples + 2 * largest, &cancel)?;
+
+    let mut master = vec![0.0f32; master_samples];
+    for (track, (track_start, span)) in tracks.iter().zip(&spans) {
+        let length = Timeline::Fixed((span.real_secs * rate) as f32);
+        let samples = render_track(track, length, out_dir, tx, cancel.clone())?;
+        let offset = ((track_start - start) * rate * sample_rate as f64) as usize * 2;
+        let gain = 10f32.powf(track.gain_db / 20.0);
+        // Constant power, scaled so a centred track keeps its level
+        let angle = (track.pan.clamp(-1.0, 1.0) + 1.0) * std::f32::consts::FRAC_PI_4;
+        let sides = [angle.cos() * std::f32::consts::SQRT_2, angle.sin() * std::f32::consts::SQRT_2];
+        for (i, (m, s)) in master.iter_mut().skip(offset).zip(&samples).enumerate() {
+            *m += s * gain * sides[i % 2];
+        }
+    }
+    // The tracks arrive at the loudness target each, so their sum is brought back to it
+    loudness::normalize(&mut master, 2, sample_rate);
+
+    let _ = tx.send(Progress::Update { name: MIX_NAME.to_string(), fraction: 0.5 });
+
+    let tmp_dir = shared::make_temp_dir("mix")?;
+    let tmp = tmp_dir.join(format!("{MIX_NAME}_tmp.wav"));
+    audio::write_wav(&tmp, &master, 2, sample_rate)?;
+
+    let args: Vec<String> = vec![
+        "-y".into(), "-hide_banner".into(), "-loglevel".into(), "error".into(), "-stats".into(),
+        "-i".into(), tmp.to_string_lossy().to_string(),
+        "-vn".into(), "-ar".into(), "44100".into(), "-ac".into(), "2".into(),
+        "-b:a".into(), "320k".into(), "-codec:a".into(), "libmp3lame".into(),
+        partial_out.to_string_lossy().to_string(),
+    ];
+    let result = shared::run_ffmpeg(&args, None, tx, MIX_NAME, cancel.clone());
+
+    let _ = fs::remove_file(&tmp);
+    let _ = fs::remove_dir_all(&tmp_dir);
+
+    if result.is_ok() && !cancel.load(Ordering::Relaxed) {
+        // A new mix replaces the last one, it is rendered from the current settings
+        let _ = fs::remove_file(out);
+        let _ = fs::rename(partial_out, out);
+        let graph = out_dir.join(format!("{MIX_NAME}_waveform.png"));
+        if let Err(e) = preview::write_waveform(&graph, &master, 2, &[]) {
+            let _ = tx.send(Progress::Warning { name: MIX_NAME.to_string(), message: format!("waveform image: {e}") });
+        }
+    } else {
+        let _ = fs::remove_file(partial_out);
+    }
+
+    result
+}
@@ -0,0 +1,129 @@
+use super::shared::Rng;
+
+/// Topology-preserving state variable filter (Simper), stable under fast
+/// cutoff and resonance modulation.
+#[derive(Clone, Default)]
+pub struct Svf {
+    g: f32,
+    k: f32,
+    a1: f32,
+    a2: f32,
+    a3: f32,
+    ic1: f32,
+    ic2: f32,
+}
+
+pub struct SvfOut {
+    pub low: f32,
+    pub band: f32,
+}
+
+impl Svf {
+    pub fn new(cutoff: f32, q: f32, sample_rate: u32) -> Self {
+        let mut f = Self::default();
+        f.set(cutoff, q, sample_rate);
+        f
+    }
+
+    pub fn set(&mut self, cutoff: f32, q: f32, sample_rate: u32) {
+        let nyquist = sample_rate as f32 * 0.5;
+        let cutoff = cutoff.clamp(10.0, nyquist * 0.95);
+        self.g = (std::f32::consts::PI * cutoff / sample_rate as f32).tan();
+        self.k = 1.0 / q.max(0.05);
+        self.a1 = 1.0 / (1.0 + self.g * (self.g + self.k));
+        self.a2 = self.g * self.a1;
+        self.a3 = self.g * self.a2;
+    }
+
+    pub fn process(&mut self, x: f32) -> SvfOut {
+        let v3 = x - self.ic2;
+        let v1 = self.a1 * self.ic1 + self.a2 * v3;
+        let v2 = self.ic2 + self.a2 * self.ic1 + self.a3 * v3;
+        self.ic1 = 2.0 * v1 - self.ic1;
+        self.ic2 = 2.0 * v2 - self.ic2;
+        SvfOut { low: v2, band: v1 }
+    }
+}
+
+/// One-pole lowpass used to glide control values.
+#[derive(Clone)]
+pub struct Smoother {
+    coef: f32,
+    value: f32,
+}
+
+impl Smoother {
+    pub fn new(time_secs: f32, sample_rate: u32, initial: f32) -> Self {
+        let coef = (-1.0 / (time_secs.max(1e-4) * sample_rate as f32)).exp();
+        Self { coef, value: initial }
+    }
+
+    pub fn next(&mut self, target: f32) -> f32 {
+        self.value = target + (self.value - target) * self.coef;
+        self.value
+    }
+}
+
+/// Pink noise from white noise with Paul Kellet's economy filter.
+#[derive(Clone)]
+pub struct PinkNoise {
+    rng: Rng,
+    b: [f32; 3],
+}
+
+impl PinkNoise {
+    pub fn new(seed: u64) -> Self {
+        Self { rng: Rng::new(seed), b: [0.0; 3] }
+    }
+
+    pub fn next(&mut self) -> f32 {
+        let white = self.rng.next_f32() * 2.0 - 1.0;
+        self.b[0] = 0.99765 * self.b[0] + white * 0.0990460;
+        self.b[1] = 0.96300 * self.b[1] + white * 0.2965164;
+        self.b[2] = 0.57000 * self.b[2] + white * 1.0526913;
+        (self.b[0] + self.b[1] + self.b[2] + white * 0.1848) * 0.25
+    }
+}
+
+/// RBJ cookbook high shelf in transposed direct form II, which tolerates its
+/// coefficients being changed while it runs.
+#[derive(Clone, Default)]
+pub struct HighShelf {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl HighShelf {
+    pub fn new(freq: f32, gain_db: f32, sample_rate: u32) -> Self {
+        let mut f = Self::default();
+        f.set(freq, gain_db, sample_rate);
+        f
+    }
+
+    pub fn set(&mut self, freq: f32, gain_db: f32, sample_rate: u32) {
+        let a = 10f32.powf(gain_db / 40.0);
+        let w0 = 2.0 * std::f32::consts::PI * freq.clamp(10.0, sample_rate as f32 * 0.45) / sample_rate as f32;
+        let (sin, cos) = w0.sin_cos();
+        // Shelf slope of 1, the steepest without a bump
+        let alpha = sin / 2.0 * 2f32.sqrt();
+        let beta = 2.0 * a.sqrt() * alpha;
+        let a0 = (a + 1.0) - (a - 1.0) * cos + beta;
+        self.b0 = a * ((a + 1.0) + (a - 1.0) * cos + beta) / a0;
+        self.b1 = -2.0 * a * ((a - 1.0) + (a + 1.0) * cos) / a0;
+        self.b2 = a * ((a + 1.0) + (a - 1.0) * cos - beta) / a0;
+        self.a1 = 2.0 * ((a - 1.0) - (a + 1.0) * cos) / a0;
+        self.a2 = ((a + 1.0) - (a - 1.0) * cos - beta) / a0;
+    }
+
+    pub fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use std::path::Path;
+
+use super::audio::{self, AudioClip};
+use super::dsp::{PinkNoise, Svf};
+use super::shared::Rng;
+
+const ACCENT_DIR: &str = "assets/wind/accents";
+
+/// Share of a swell spent rising to its peak, which lands on the reading.
+const SWELL_RISE: f32 = 0.35;
+
+/// What plays when a gust is detected.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccentSound {
+    /// A burst of swept noise synthesized per gust
+    Swell,
+    /// One-shot recordings from `assets/wind/accents/`, picked at random
+    Samples,
+}
+
+#[derive(Clone, Debug)]
+pub struct GustSettings {
+    pub enabled: bool,
+    /// Rising through this reading is a gust, in input units
+    pub threshold: f32,
+    /// A rise of at least this much from one reading to the next is a gust on
+    /// its own, wherever it happens
+    pub jump: f32,
+    pub sound: AccentSound,
+    /// Accent loudness relative to the wind around it
+    pub level: f32,
+}
+
+impl Default for GustSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 8.0,
+            jump: 3.0,
+            sound: AccentSound::Swell,
+            level: 1.5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GustEvent {
+    /// Reading the gust peaks on
+    pub index: usize,
+    /// 1 for a gust just meeting the criteria, more for sharper rises
+    pub strength: f32,
+}
+
+/// Finds threshold crossings and peaks in the rate of change of a series.
+pub fn detect(values: &[f32], settings: &GustSettings) -> Vec<GustEvent> {
+    let rise = |i: usize| if i == 0 || i >= values.len() { 0.0 } else { values[i] - values[i - 1] };
+    let jump = settings.jump.max(f32::EPSILON);
+
+    (1..values.len())
+        .filter_map(|i| {
+            let crossing = values[i - 1] < settings.threshold && values[i] >= settings.threshold;
+            // A steep rise spread over several readings counts once, at its steepest
+            let surge = rise(i) >= settings.jump && rise(i) >= rise(i - 1) && rise(i) > rise(i + 1);
+            (crossing || surge).then(|| GustEvent { index: i, strength: (rise(i) / jump).clamp(1.0, 3.0) })
+        })
+        .collect()
+}
+
+/// Hands out one accent per gust.
+pub struct Accents {
+    sample_rate: u32,
+    clips: Vec<AudioClip>,
+    rng: Rng,
+}
+
+impl Accents {
+    pub fn new(sound: AccentSound, seed: u64, sample_rate: u32) -> Result<Self, String> {
+        let mut clips = Vec::new();
+        if sound == AccentSound::Samples {
+            let dir = Path::new(ACCENT_DIR);
+            let entries = fs::read_dir(dir).map_err(|e| format!("read_dir {}: {e}", dir.display()))?;
+            let mut files: Vec<_> = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().and_then(|e| e.to_str()).map(|e| e.eq_ignore_ascii_case("wav")) == Some(true))
+                .collect();
+            files.sort();
+            for file in &files {
+                clips.push(audio::load_clip(file, sample_rate)?);
+            }
+            if clips.is_empty() {
+                return Err(format!("No accent recordings found in {ACCENT_DIR}"));
+            }
+        }
+        Ok(Self { sample_rate, clips, rng: Rng::new(seed ^ 0xACCE_0000) })
+    }
+
+    /// The accent for a gust and the frame within it that should meet the reading.
+    pub fn next(&mut self, strength: f32) -> (AudioClip, usize) {
+        if self.clips.is_empty() {
+            let clip = self.swell(strength);
+            let peak = (clip.frames() as f32 * SWELL_RISE) as usize;
+            return (clip, peak);
+        }
+        let pick = (self.rng.next_u64() % self.clips.len() as u64) as usize;
+        (self.clips[pick].clone(), 0)
+    }
+
+    /// Stereo noise through a bandpass that sweeps up with the rise of the gust
+    /// and back down as it dies away. Stronger gusts last longer and sweep higher.
+    fn swell(&mut self, strength: f32) -> AudioClip {
+        let secs = (2.0 + 1.5 * self.rng.next_f32()) * (0.75 + 0.25 * strength);
+        let frames = (secs * self.sample_rate as f32) as usize;
+        let top = 1200.0 + 1000.0 * strength + 600.0 * self.rng.next_f32();
+        let q = 1.2 + self.rng.next_f32();
+
+        let mut noise = [PinkNoise::new(self.rng.next_u64()), PinkNoise::new(self.rng.next_u64())];
+        let mut filters = [Svf::new(250.0, q, self.sample_rate), Svf::new(250.0, q, self.sample_rate)];
+        let mut channels = vec![vec![0.0f32; frames]; 2];
+
+        for i in 0..frames {
+            let t = i as f32 / frames as f32;
+            let env = if t < SWELL_RISE {
+                (t / SWELL_RISE * std::f32::consts::FRAC_PI_2).sin().powi(2)
+            } else {
+                ((t - SWELL_RISE) / (1.0 - SWELL_RISE) * std::f32::consts::FRAC_PI_2).cos().powi(2)
+            };
+            if i % 16 == 0 {
+                let cutoff = 250.0 * (top / 250.0f32).powf(env);
+                for f in filters.iter_mut() {
+                    f.set(cutoff, q, self.sample_rate);
+                }
+            }
+            for ((channel, f), n) in channels.iter_mut().zip(filters.iter_mut()).zip(noise.iter_mut()) {
+                let out = f.process(n.next());
+                channel[i] = (out.band + 0.5 * out.low) * env;
+            }
+        }
+        AudioClip { sample_rate: self.sample_rate, channels }
+    }
+}
@@ -0,0 +1,116 @@
+/// How a converter moves between consecutive readings.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Interpolation {
+    /// Holds each reading until the next one
+    Step,
+    #[default]
+    Linear,
+    /// Eases in and out of every reading
+    Cosine,
+    /// Smooth curve that never overshoots the readings around it
+    MonotoneCubic,
+    /// Linear through readings averaged over a window of this many readings
+    Smooth(f32),
+}
+
+/// A reading series sampled continuously between its first and last value.
+pub struct Series {
+    mode: Interpolation,
+    values: Vec<f32>,
+    /// Hermite tangents for `MonotoneCubic`, empty otherwise
+    tangents: Vec<f32>,
+}
+
+impl Series {
+    pub fn new(values: &[f32], mode: Interpolation) -> Self {
+        let values = match mode {
+            Interpolation::Smooth(window) => moving_average(values, window),
+            _ => values.to_vec(),
+        };
+        let tangents = if mode == Interpolation::MonotoneCubic { monotone_tangents(&values) } else { Vec::new() };
+        Self { mode, values, tangents }
+    }
+
+    /// Value at `progress` in 0..1, the first reading at 0 and the last at 1.
+    pub fn at(&self, progress: f64) -> f32 {
+        self.at_index(progress.clamp(0.0, 1.0) * self.values.len().saturating_sub(1) as f64)
+    }
+
+    /// Value at a fractional reading index.
+    pub fn at_index(&self, index: f64) -> f32 {
+        let n = self.values.len();
+        if n == 0 {
+            return 0.0;
+        }
+        let index = index.clamp(0.0, (n - 1) as f64);
+        let i = (index.floor() as usize).min(n - 1);
+        let j = (i + 1).min(n - 1);
+        let t = (index - i as f64) as f32;
+        let (a, b) = (self.values[i], self.values[j]);
+
+        match self.mode {
+            Interpolation::Step => a,
+            Interpolation::Linear | Interpolation::Smooth(_) => a + (b - a) * t,
+            Interpolation::Cosine => {
+                let eased = (1.0 - (t * std::f32::consts::PI).cos()) * 0.5;
+                a + (b - a) * eased
+            }
+            Interpolation::MonotoneCubic => {
+                let (t2, t3) = (t * t, t * t * t);
+                let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+                let h10 = t3 - 2.0 * t2 + t;
+                let h01 = -2.0 * t3 + 3.0 * t2;
+                let h11 = t3 - t2;
+                h00 * a + h10 * self.tangents[i] + h01 * b + h11 * self.tangents[j]
+            }
+        }
+    }
+}
+
+/// Centred moving average, the window narrowing towards the ends of the series.
+fn moving_average(values: &[f32], window: f32) -> Vec<f32> {
+    let half = ((window.max(1.0) - 1.0) / 2.0).round() as usize;
+    let mut prefix = vec![0.0f64; values.len() + 1];
+    for (i, v) in values.iter().enumerate() {
+        prefix[i + 1] = prefix[i] + *v as f64;
+    }
+    (0..values.len())
+        .map(|i| {
+            let lo = i.saturating_sub(half);
+            let hi = (i + half + 1).min(values.len());
+            ((prefix[hi] - prefix[lo]) / (hi - lo) as f64) as f32
+        })
+        .collect()
+}
+
+/// Fritsch-Carlson tangents, flattened where the data turns so the curve stays
+/// within the readings on either side.
+fn monotone_tangents(values: &[f32]) -> Vec<f32> {
+    let n = values.len();
+    if n < 2 {
+        return vec![0.0; n];
+    }
+    let slopes: Vec<f32> = values.windows(2).map(|w| w[1] - w[0]).collect();
+    let mut tangents = vec![0.0f32; n];
+    tangents[0] = slopes[0];
+    tangents[n - 1] = slopes[n - 2];
+    for i in 1..n - 1 {
+        tangents[i] = if slopes[i - 1] * slopes[i] <= 0.0 { 0.0 } else { (slopes[i - 1] + slopes[i]) / 2.0 };
+    }
+    for (i, &slope) in slopes.iter().enumerate() {
+        if slope == 0.0 {
+            tangents[i] = 0.0;
+            tangents[i + 1] = 0.0;
+            continue;
+        }
+        let a = tangents[i] / slope;
+        let b = tangents[i + 1] / slope;
+        let sum = a * a + b * b;
+        if sum > 9.0 {
+            let tau = 3.0 / sum.sqrt();
+            tangents[i] = tau * a * slope;
+            tangents[i + 1] = tau * b * slope;
+        }
+    }
+    tangents
+}
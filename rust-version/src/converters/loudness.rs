@@ -0,0 +1,246 @@
+use super::shared;
+
+const BLOCK_SECS: f64 = 0.4;
+const HOP_SECS: f64 = 0.1;
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE: f64 = -10.0;
+
+const OVERSAMPLE: usize = 4;
+const TAPS: usize = 12;
+const LOOKAHEAD_SECS: f64 = 0.005;
+const RELEASE_SECS: f64 = 0.15;
+
+/// Second-order IIR section in direct form I.
+#[derive(Clone, Copy)]
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    x1: f64,
+    x2: f64,
+    y1: f64,
+    y2: f64,
+}
+
+impl Biquad {
+    fn new(b0: f64, b1: f64, b2: f64, a1: f64, a2: f64) -> Self {
+        Self { b0, b1, b2, a1, a2, x1: 0.0, x2: 0.0, y1: 0.0, y2: 0.0 }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2 - self.a1 * self.y1 - self.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+/// The two-stage K-weighting filter of ITU-R BS.1770, derived for any sample rate.
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let fs = sample_rate as f64;
+
+    // Stage 1: high shelf modelling the acoustic effect of the head
+    let f0 = 1681.974450955533;
+    let gain_db = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (std::f64::consts::PI * f0 / fs).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad::new(
+        (vh + vb * k / q + k * k) / a0,
+        2.0 * (k * k - vh) / a0,
+        (vh - vb * k / q + k * k) / a0,
+        2.0 * (k * k - 1.0) / a0,
+        (1.0 - k / q + k * k) / a0,
+    );
+
+    // Stage 2: RLB high-pass
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (std::f64::consts::PI * f0 / fs).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let highpass = Biquad::new(
+        1.0,
+        -2.0,
+        1.0,
+        2.0 * (k * k - 1.0) / a0,
+        (1.0 - k / q + k * k) / a0,
+    );
+
+    [shelf, highpass]
+}
+
+/// Integrated loudness in LUFS of an interleaved buffer, gated as in EBU R128.
+/// Mono buffers are measured as dual-mono since the encoder plays them on both
+/// speakers. Returns `None` when everything falls below the absolute gate.
+pub fn integrated_loudness(samples: &[f32], channels: usize, sample_rate: u32) -> Option<f64> {
+    let channels = channels.max(1);
+    let frames = samples.len() / channels;
+    let hop = (HOP_SECS * sample_rate as f64) as usize;
+    let hops_per_block = (BLOCK_SECS / HOP_SECS).round() as usize;
+    if hop == 0 || frames < hop * hops_per_block {
+        return None;
+    }
+
+    // Mean square of the K-weighted signal per 100 ms hop, summed over channels
+    let mut hop_power = vec![0.0f64; frames / hop];
+    for ch in 0..channels {
+        let mut filters = k_weighting(sample_rate);
+        for (h, power) in hop_power.iter_mut().enumerate() {
+            let mut acc = 0.0;
+            for i in h * hop..(h + 1) * hop {
+                let mut x = samples[i * channels + ch] as f64;
+                for f in filters.iter_mut() {
+                    x = f.process(x);
+                }
+                acc += x * x;
+            }
+            *power += acc / hop as f64;
+        }
+    }
+    if channels == 1 {
+        hop_power.iter_mut().for_each(|p| *p *= 2.0);
+    }
+
+    // 400 ms blocks with 75% overlap
+    let blocks: Vec<f64> = hop_power
+        .windows(hops_per_block)
+        .map(|w| w.iter().sum::<f64>() / hops_per_block as f64)
+        .collect();
+
+    let loudness = |power: f64| -0.691 + 10.0 * power.log10();
+    let gated_mean = |threshold: f64| {
+        let kept: Vec<f64> = blocks.iter().cloned().filter(|&p| p > 0.0 && loudness(p) > threshold).collect();
+        if kept.is_empty() {
+            None
+        } else {
+            Some(kept.iter().sum::<f64>() / kept.len() as f64)
+        }
+    };
+
+    let abs_mean = gated_mean(ABSOLUTE_GATE)?;
+    let relative_threshold = loudness(abs_mean) + RELATIVE_GATE;
+    gated_mean(relative_threshold.max(ABSOLUTE_GATE)).map(loudness)
+}
+
+/// Windowed-sinc kernels interpolating the three in-between positions of 4x
+/// oversampling, as used for true-peak estimation.
+fn true_peak_kernels() -> [[f32; TAPS]; OVERSAMPLE] {
+    let mut kernels = [[0.0f32; TAPS]; OVERSAMPLE];
+    let half = (TAPS / 2) as f32;
+    for (phase, kernel) in kernels.iter_mut().enumerate() {
+        for (j, tap) in kernel.iter_mut().enumerate() {
+            let t = j as f32 - (half - 1.0) - phase as f32 / OVERSAMPLE as f32;
+            let sinc = if t.abs() < 1e-6 {
+                1.0
+            } else {
+                (std::f32::consts::PI * t).sin() / (std::f32::consts::PI * t)
+            };
+            let window = 0.5 * (1.0 + (std::f32::consts::PI * t / half).cos());
+            *tap = sinc * window;
+        }
+    }
+    kernels
+}
+
+/// Per-frame true-peak estimate (linked across channels).
+fn true_peaks(samples: &[f32], channels: usize, ceiling: f32) -> Vec<f32> {
+    let frames = samples.len() / channels;
+    let kernels = true_peak_kernels();
+    let offset = TAPS / 2 - 1;
+    let mut peaks = vec![0.0f32; frames];
+
+    for (n, peak) in peaks.iter_mut().enumerate() {
+        for ch in 0..channels {
+            let sample = samples[n * channels + ch].abs();
+            *peak = peak.max(sample);
+
+            // Inter-sample overs stay within a few dB of the sample peaks, so the
+            // interpolation is only worth running where the signal gets close.
+            let next = samples.get((n + 1) * channels + ch).map_or(0.0, |s| s.abs());
+            if sample.max(next) < ceiling * 0.5 {
+                continue;
+            }
+            for kernel in kernels.iter().skip(1) {
+                let mut acc = 0.0f32;
+                for (j, tap) in kernel.iter().enumerate() {
+                    let idx = (n + j).wrapping_sub(offset);
+                    if idx < frames {
+                        acc += samples[idx * channels + ch] * tap;
+                    }
+                }
+                *peak = peak.max(acc.abs());
+            }
+        }
+    }
+    peaks
+}
+
+/// Look-ahead limiter keeping the true peak of the interleaved buffer at or below
+/// `ceiling_db` dBTP without the distortion of a hard clip.
+pub fn true_peak_limit(samples: &mut [f32], channels: usize, sample_rate: u32, ceiling_db: f64) {
+    let channels = channels.max(1);
+    let frames = samples.len() / channels;
+    if frames == 0 {
+        return;
+    }
+    let ceiling = 10f64.powf(ceiling_db / 20.0) as f32;
+    let peaks = true_peaks(samples, channels, ceiling);
+    if peaks.iter().all(|&p| p <= ceiling) {
+        return;
+    }
+
+    let lookahead = ((LOOKAHEAD_SECS * sample_rate as f64) as usize).max(1);
+    let release = (-1.0 / (RELEASE_SECS * sample_rate as f64)).exp() as f32;
+    let required: Vec<f32> = peaks.iter().map(|&p| if p > ceiling { ceiling / p } else { 1.0 }).collect();
+
+    // Minimum of the required gain over the upcoming look-ahead window, with an
+    // exponential release back towards unity
+    let mut window_min = vec![1.0f32; frames];
+    let mut deque: std::collections::VecDeque<usize> = std::collections::VecDeque::new();
+    for i in (0..frames).rev() {
+        while deque.back().is_some_and(|&j| required[j] >= required[i]) {
+            deque.pop_back();
+        }
+        deque.push_back(i);
+        while deque.front().is_some_and(|&j| j >= i + lookahead) {
+            deque.pop_front();
+        }
+        window_min[i] = required[*deque.front().unwrap()];
+    }
+    let mut held = 1.0f32;
+    for g in window_min.iter_mut() {
+        held = g.min(1.0 - (1.0 - held) * release);
+        *g = held;
+    }
+
+    // Averaging over the look-ahead length keeps the gain under every requirement
+    // it overlaps while turning the steps into smooth ramps.
+    let mut sum = 0.0f64;
+    for n in 0..frames {
+        sum += window_min[n] as f64;
+        if n >= lookahead {
+            sum -= window_min[n - lookahead] as f64;
+        }
+        let gain = sum as f32 / (n + 1).min(lookahead) as f32;
+        for s in &mut samples[n * channels..(n + 1) * channels] {
+            // The clamp only catches float rounding at the exact ceiling
+            *s = (*s * gain).clamp(-ceiling, ceiling);
+        }
+    }
+}
+
+/// Brings the buffer to the configured integrated loudness and true-peak ceiling.
+/// Returns the measured loudness before normalization.
+pub fn normalize(samples: &mut [f32], channels: usize, sample_rate: u32) -> Option<f64> {
+    let measured = integrated_loudness(samples, channels, sample_rate)?;
+    let gain = 10f64.powf((shared::target_loudness() - measured) / 20.0) as f32;
+    samples.iter_mut().for_each(|s| *s *= gain);
+    true_peak_limit(samples, channels, sample_rate, shared::true_peak_ceiling());
+    Some(measured)
+}
@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::Path;
+
+/// Ticks per quarter note.
+const PPQ: u16 = 480;
+
+/// Controller values per second in the curve files.
+const CURVE_RATE: f64 = 50.0;
+
+/// Tempo of the curve files, only there so ticks map to a fixed slice of time.
+const CURVE_TEMPO_US: u32 = 500_000;
+
+/// General MIDI drum channel, counted from 0.
+const DRUM_CHANNEL: u8 = 9;
+/// Acoustic bass drum in the General MIDI drum map.
+const HEART_NOTE: u8 = 36;
+
+/// One controller lane of a curve file.
+pub struct Controller {
+    pub number: u8,
+    pub name: &'static str,
+    /// 0..1 at `CURVE_RATE` values per second
+    values: Vec<f32>,
+}
+
+impl Controller {
+    /// Samples `value` (0..1 for `progress` 0..1) across `duration_secs`.
+    pub fn new(number: u8, name: &'static str, duration_secs: f64, value: impl Fn(f64) -> f32) -> Self {
+        let count = ((duration_secs * CURVE_RATE).ceil() as usize).max(1);
+        let last = (count - 1).max(1) as f64;
+        let values = (0..count).map(|i| value(i as f64 / last).clamp(0.0, 1.0)).collect();
+        Self { number, name, values }
+    }
+}
+
+/// Variable-length quantity as used for delta times.
+fn push_vlq(out: &mut Vec<u8>, mut value: u32) {
+    let mut bytes = vec![(value & 0x7F) as u8];
+    value >>= 7;
+    while value > 0 {
+        bytes.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    out.extend(bytes.iter().rev());
+}
+
+/// Events of one track, each at an absolute tick.
+#[derive(Default)]
+struct Track {
+    events: Vec<(u64, Vec<u8>)>,
+}
+
+impl Track {
+    fn named(name: &str) -> Self {
+        let mut track = Track::default();
+        let mut meta = vec![0xFF, 0x03];
+        push_vlq(&mut meta, name.len() as u32);
+        meta.extend(name.as_bytes());
+        track.events.push((0, meta));
+        track
+    }
+
+    fn tempo(&mut self, tick: u64, us_per_quarter: u32) {
+        let us = us_per_quarter.clamp(1, 0xFF_FFFF).to_be_bytes();
+        self.events.push((tick, vec![0xFF, 0x51, 0x03, us[1], us[2], us[3]]));
+    }
+
+    fn encode(mut self) -> Vec<u8> {
+        // Stable, so note-offs stay ahead of note-ons on the same tick
+        self.events.sort_by_key(|(tick, _)| *tick);
+        let mut data = Vec::new();
+        let mut last = 0;
+        for (tick, event) in &self.events {
+            push_vlq(&mut data, (tick - last) as u32);
+            data.extend(event);
+            last = *tick;
+        }
+        data.extend([0x00, 0xFF, 0x2F, 0x00]);
+
+        let mut chunk = b"MTrk".to_vec();
+        chunk.extend((data.len() as u32).to_be_bytes());
+        chunk.extend(data);
+        chunk
+    }
+}
+
+fn write_smf(path: &Path, tracks: Vec<Track>) -> Result<(), String> {
+    let mut file = b"MThd".to_vec();
+    file.extend(6u32.to_be_bytes());
+    file.extend(1u16.to_be_bytes());
+    file.extend((tracks.len() as u16).to_be_bytes());
+    file.extend(PPQ.to_be_bytes());
+    for track in tracks {
+        file.extend(track.encode());
+    }
+    fs::write(path, file).map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+/// One quarter note per heartbeat on the drum channel, with a tempo change on
+/// every beat so the DAW grid follows the heart. `beats` are the start times
+/// in seconds, `end_secs` where the last beat's interval ends.
+pub fn write_beats(path: &Path, beats: &[f64], end_secs: f64) -> Result<(), String> {
+    let mut tempo = Track::named("Tempo");
+    let mut notes = Track::named("Heartbeat");
+    let quarter = PPQ as u64;
+
+    // A lead-in before the first beat gets a quarter of its own
+    let mut tick = 0;
+    if let Some(&first) = beats.first().filter(|t| **t > 0.0) {
+        tempo.tempo(0, (first * 1e6) as u32);
+        tick = quarter;
+    }
+    for (i, &start) in beats.iter().enumerate() {
+        let next = beats.get(i + 1).copied().unwrap_or(end_secs);
+        tempo.tempo(tick, ((next - start).max(1e-3) * 1e6) as u32);
+        notes.events.push((tick, vec![0x90 | DRUM_CHANNEL, HEART_NOTE, 100]));
+        notes.events.push((tick + quarter / 4, vec![0x80 | DRUM_CHANNEL, HEART_NOTE, 0]));
+        tick += quarter;
+    }
+    write_smf(path, vec![tempo, notes])
+}
+
+/// One track per controller on channel 1, written only where the value moves.
+pub fn write_curves(path: &Path, controllers: &[Controller]) -> Result<(), String> {
+    let mut tempo = Track::named("Tempo");
+    tempo.tempo(0, CURVE_TEMPO_US);
+    let ticks_per_sec = PPQ as f64 * 1e6 / CURVE_TEMPO_US as f64;
+
+    let mut tracks = vec![tempo];
+    for controller in controllers {
+        let mut track = Track::named(controller.name);
+        let mut previous = None;
+        for (i, value) in controller.values.iter().enumerate() {
+            let value = (value * 127.0).round() as u8;
+            if previous != Some(value) {
+                let tick = (i as f64 / CURVE_RATE * ticks_per_sec).round() as u64;
+                track.events.push((tick, vec![0xB0, controller.number & 0x7F, value]));
+                previous = Some(value);
+            }
+        }
+        tracks.push(track);
+    }
+    write_smf(path, tracks)
+}
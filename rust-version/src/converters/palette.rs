@@ -0,0 +1,176 @@
+use std::fs;
+use std::path::Path;
+use image::{Rgb, RgbImage};
+
+use super::rgb_data::{RgbDataset, RgbEntry};
+
+const BACKGROUND: [u8; 3] = [255, 255, 255];
+
+/// Side of one swatch in the strip and grid, shrunk so long series stay printable.
+const SWATCH: u32 = 64;
+const MIN_SWATCH: u32 = 8;
+const GAP: u32 = 4;
+/// Strips wider than this get smaller swatches.
+const MAX_STRIP_W: u32 = 4096;
+
+const BARCODE_W: u32 = 1200;
+const BARCODE_H: u32 = 300;
+
+/// Swatches per row in the GIMP palette editor.
+const GPL_COLUMNS: usize = 8;
+
+/// How the swatch image lays out the colours.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PaletteStyle {
+    /// No palette files
+    #[default]
+    Off,
+    /// One row of swatches in reading order
+    Strip,
+    /// A square-ish grid of swatches, read row by row
+    Grid,
+    /// Touching bars, one per colour, across a fixed width
+    Barcode,
+}
+
+/// Where one colour lands in the image, `w` and `h` in pixels.
+struct Rect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+/// Image size and one rectangle per colour.
+fn layout(style: PaletteStyle, count: usize) -> (u32, u32, Vec<Rect>) {
+    let n = count.max(1) as u32;
+    match style {
+        PaletteStyle::Off | PaletteStyle::Strip => {
+            let swatch = ((MAX_STRIP_W - GAP) / n).saturating_sub(GAP).clamp(MIN_SWATCH, SWATCH);
+            let rects = (0..count)
+                .map(|i| Rect { x: (GAP + i as u32 * (swatch + GAP)) as f32, y: GAP as f32, w: swatch as f32, h: swatch as f32 })
+                .collect();
+            (GAP + n * (swatch + GAP), swatch + 2 * GAP, rects)
+        }
+        PaletteStyle::Grid => {
+            let cols = (n as f64).sqrt().ceil() as u32;
+            let rows = n.div_ceil(cols);
+            let swatch = ((MAX_STRIP_W - GAP) / cols).saturating_sub(GAP).clamp(MIN_SWATCH, SWATCH);
+            let rects = (0..count as u32)
+                .map(|i| Rect {
+                    x: (GAP + (i % cols) * (swatch + GAP)) as f32,
+                    y: (GAP + (i / cols) * (swatch + GAP)) as f32,
+                    w: swatch as f32,
+                    h: swatch as f32,
+                })
+                .collect();
+            (GAP + cols * (swatch + GAP), GAP + rows * (swatch + GAP), rects)
+        }
+        PaletteStyle::Barcode => {
+            let bar = BARCODE_W as f32 / n as f32;
+            let rects = (0..count).map(|i| Rect { x: i as f32 * bar, y: 0.0, w: bar, h: BARCODE_H as f32 }).collect();
+            (BARCODE_W, BARCODE_H, rects)
+        }
+    }
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02X}{g:02X}{b:02X}")
+}
+
+/// Swatch name in the palette files: the day label, if any, and the hex code.
+fn swatch_name(entry: &RgbEntry) -> String {
+    match &entry.label {
+        Some(label) => format!("{label} {}", hex(entry.color)),
+        None => hex(entry.color),
+    }
+}
+
+fn write_png(path: &Path, style: PaletteStyle, entries: &[RgbEntry]) -> Result<(), String> {
+    let (w, h, rects) = layout(style, entries.len());
+    let mut img = RgbImage::from_pixel(w, h, Rgb(BACKGROUND));
+    for (rect, entry) in rects.iter().zip(entries) {
+        // Bars have fractional widths, rounding both edges keeps neighbours touching
+        let (x0, x1) = (rect.x.round() as u32, ((rect.x + rect.w).round() as u32).min(w));
+        let (y0, y1) = (rect.y as u32, ((rect.y + rect.h) as u32).min(h));
+        for y in y0..y1 {
+            for x in x0..x1 {
+                img.put_pixel(x, y, Rgb(entry.color));
+            }
+        }
+    }
+    img.save(path).map_err(|e| format!("save {}: {e}", path.display()))
+}
+
+fn write_svg(path: &Path, style: PaletteStyle, entries: &[RgbEntry]) -> Result<(), String> {
+    let (w, h, rects) = layout(style, entries.len());
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" shape-rendering=\"crispEdges\">\n"
+    );
+    svg.push_str(&format!("  <rect width=\"{w}\" height=\"{h}\" fill=\"{}\"/>\n", hex(BACKGROUND)));
+    for (rect, entry) in rects.iter().zip(entries) {
+        // The title shows up as a tooltip and keeps the day with each swatch
+        svg.push_str(&format!(
+            "  <rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\"><title>{}</title></rect>\n",
+            rect.x,
+            rect.y,
+            rect.w,
+            rect.h,
+            hex(entry.color),
+            swatch_name(entry)
+        ));
+    }
+    svg.push_str("</svg>\n");
+    fs::write(path, svg).map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+/// GIMP palette, also read by Inkscape and Krita.
+fn write_gpl(path: &Path, name: &str, entries: &[RgbEntry]) -> Result<(), String> {
+    let mut gpl = format!("GIMP Palette\nName: {name}\nColumns: {GPL_COLUMNS}\n#\n");
+    for entry in entries {
+        let [r, g, b] = entry.color;
+        gpl.push_str(&format!("{r:>3} {g:>3} {b:>3}\t{}\n", swatch_name(entry)));
+    }
+    fs::write(path, gpl).map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+/// Adobe Swatch Exchange: big-endian blocks, names in UTF-16 with a terminating null.
+fn write_ase(path: &Path, entries: &[RgbEntry]) -> Result<(), String> {
+    let mut ase = Vec::new();
+    ase.extend(b"ASEF");
+    ase.extend(1u16.to_be_bytes());
+    ase.extend(0u16.to_be_bytes());
+    ase.extend((entries.len() as u32).to_be_bytes());
+
+    for entry in entries {
+        let name: Vec<u16> = swatch_name(entry).encode_utf16().chain([0]).collect();
+        let mut block = Vec::new();
+        block.extend((name.len() as u16).to_be_bytes());
+        for unit in &name {
+            block.extend(unit.to_be_bytes());
+        }
+        block.extend(b"RGB ");
+        for c in entry.color {
+            block.extend((c as f32 / 255.0).to_be_bytes());
+        }
+        // Global colour, as opposed to spot or process
+        block.extend(0u16.to_be_bytes());
+
+        ase.extend(0x0001u16.to_be_bytes());
+        ase.extend((block.len() as u32).to_be_bytes());
+        ase.extend(block);
+    }
+    fs::write(path, ase).map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+/// Writes `{name}_palette.png` and `.svg` in `style`, plus `{name}.gpl` and
+/// `{name}.ase`, from the parsed entries in reading order.
+pub fn write(data: &RgbDataset, style: PaletteStyle, out_dir: &Path, name: &str) -> Result<(), String> {
+    if style == PaletteStyle::Off || data.entries.is_empty() {
+        return Ok(());
+    }
+    write_png(&out_dir.join(format!("{name}_palette.png")), style, &data.entries)?;
+    write_svg(&out_dir.join(format!("{name}_palette.svg")), style, &data.entries)?;
+    write_gpl(&out_dir.join(format!("{name}.gpl")), name, &data.entries)?;
+    write_ase(&out_dir.join(format!("{name}.ase")), &data.entries)
+}
@@ -0,0 +1,173 @@
+use std::path::Path;
+use image::{imageops, Rgb, RgbImage};
+use imageproc::drawing::{draw_filled_circle_mut, draw_line_segment_mut};
+
+use super::shared::{self, PosterAt};
+use super::{Progress, ProgressTx};
+
+const THUMB_W: u32 = 192;
+const SHEET_COLS: u32 = 4;
+const SHEET_GAP: u32 = 4;
+
+const GRAPH_W: u32 = 1200;
+const GRAPH_H: u32 = 320;
+const GRAPH_BG: Rgb<u8> = Rgb([150, 158, 123]);
+const GRAPH_WAVE: Rgb<u8> = Rgb([62, 84, 114]);
+const GRAPH_AXIS: Rgb<u8> = Rgb([117, 122, 97]);
+const GRAPH_DATA: Rgb<u8> = Rgb([109, 74, 56]);
+
+/// Collects the poster frame and contact sheet thumbnails while a converter
+/// streams its frames, so the preview images never need the video decoded again.
+pub struct PreviewCapture {
+    width: u32,
+    height: u32,
+    poster_frame: usize,
+    thumb_frames: Vec<usize>,
+    poster: Option<RgbImage>,
+    thumbs: Vec<RgbImage>,
+}
+
+impl PreviewCapture {
+    pub fn new(width: u32, height: u32, total_frames: usize, fps: f32) -> Self {
+        let last = total_frames.saturating_sub(1);
+        let poster_frame = match shared::poster_position() {
+            PosterAt::Frame(f) => f,
+            PosterAt::Seconds(s) => (s * fps).round() as usize,
+            PosterAt::Fraction(p) => (p * last as f32).round() as usize,
+        }
+        .min(last);
+
+        // Thumbnails sit in the middle of equal slices so the black lead-in and
+        // lead-out frames of the scrolling converters are skipped.
+        let count = shared::thumbnail_count();
+        let thumb_frames = (0..count)
+            .map(|i| (((i as f32 + 0.5) / count as f32) * last as f32).round() as usize)
+            .collect();
+
+        Self {
+            width,
+            height,
+            poster_frame,
+            thumb_frames,
+            poster: None,
+            thumbs: Vec::new(),
+        }
+    }
+
+    pub fn wants(&self, frame: usize) -> bool {
+        frame == self.poster_frame || self.thumb_frames.contains(&frame)
+    }
+
+    /// Offers a raw rgb24 frame of the size given to `new`.
+    pub fn offer(&mut self, frame: usize, rgb: &[u8]) {
+        if !self.wants(frame) {
+            return;
+        }
+        if let Some(img) = RgbImage::from_raw(self.width, self.height, rgb.to_vec()) {
+            self.offer_image(frame, img);
+        }
+    }
+
+    pub fn offer_image(&mut self, frame: usize, img: RgbImage) {
+        for _ in self.thumb_frames.iter().filter(|&&f| f == frame) {
+            let h = ((img.height() as f32 * THUMB_W as f32 / img.width().max(1) as f32).round() as u32).max(1);
+            self.thumbs.push(imageops::resize(&img, THUMB_W, h, imageops::FilterType::Triangle));
+        }
+        if frame == self.poster_frame {
+            self.poster = Some(img);
+        }
+    }
+
+    /// Writes `<stem>_poster` and `<stem>_thumbs` next to the finished video.
+    /// The video stands on its own, so a failure here is only a warning.
+    pub fn write(self, video: &Path, tx: &ProgressTx, name: &str) {
+        if let Err(e) = self.save(video) {
+            let _ = tx.send(Progress::Warning { name: name.to_string(), message: format!("preview images: {e}") });
+        }
+    }
+
+    fn save(self, video: &Path) -> Result<(), String> {
+        let ext = shared::poster_extension();
+        let stem = video.file_stem().unwrap_or_default().to_string_lossy().to_string();
+
+        if let Some(poster) = &self.poster {
+            let path = video.with_file_name(format!("{stem}_poster.{ext}"));
+            poster.save(&path).map_err(|e| format!("save {}: {e}", path.display()))?;
+        }
+
+        if !self.thumbs.is_empty() {
+            let cols = SHEET_COLS.min(self.thumbs.len() as u32);
+            let rows = (self.thumbs.len() as u32).div_ceil(cols);
+            let cell_h = self.thumbs.iter().map(|t| t.height()).max().unwrap_or(1);
+            let mut sheet = RgbImage::new(
+                cols * THUMB_W + (cols + 1) * SHEET_GAP,
+                rows * cell_h + (rows + 1) * SHEET_GAP,
+            );
+            for (i, thumb) in self.thumbs.iter().enumerate() {
+                let col = i as u32 % cols;
+                let row = i as u32 / cols;
+                let x = SHEET_GAP + col * (THUMB_W + SHEET_GAP);
+                let y = SHEET_GAP + row * (cell_h + SHEET_GAP);
+                imageops::replace(&mut sheet, thumb, x as i64, y as i64);
+            }
+            let path = video.with_file_name(format!("{stem}_thumbs.{ext}"));
+            sheet.save(&path).map_err(|e| format!("save {}: {e}", path.display()))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders the amplitude envelope of an interleaved sample buffer with the parsed
+/// data series plotted over it, for listing audio-only outputs.
+pub fn write_waveform(path: &Path, samples: &[f32], channels: usize, series: &[f32]) -> Result<(), String> {
+    let mut img = RgbImage::from_pixel(GRAPH_W, GRAPH_H, GRAPH_BG);
+    let mid = GRAPH_H as f32 / 2.0;
+    let half = mid - 8.0;
+    draw_line_segment_mut(&mut img, (0.0, mid), (GRAPH_W as f32, mid), GRAPH_AXIS);
+
+    let channels = channels.max(1);
+    let frames = samples.len() / channels;
+    if frames > 0 {
+        for x in 0..GRAPH_W {
+            let start = frames * x as usize / GRAPH_W as usize;
+            let end = (frames * (x as usize + 1) / GRAPH_W as usize).max(start + 1).min(frames);
+            let (mut lo, mut hi) = (0.0f32, 0.0f32);
+            for s in &samples[start * channels..end * channels] {
+                lo = lo.min(*s);
+                hi = hi.max(*s);
+            }
+            let top = mid - hi.clamp(-1.0, 1.0) * half;
+            let bottom = mid - lo.clamp(-1.0, 1.0) * half;
+            draw_line_segment_mut(&mut img, (x as f32, top), (x as f32, bottom), GRAPH_WAVE);
+        }
+    }
+
+    if !series.is_empty() {
+        let min = series.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = series.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let span = (max - min).max(f32::EPSILON);
+        let last = (series.len() - 1).max(1) as f32;
+        let points: Vec<(f32, f32)> = series
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let x = i as f32 / last * (GRAPH_W - 1) as f32;
+                let y = if max > min { (GRAPH_H - 12) as f32 - (v - min) / span * (GRAPH_H - 24) as f32 } else { mid };
+                (x, y)
+            })
+            .collect();
+        for w in points.windows(2) {
+            draw_line_segment_mut(&mut img, w[0], w[1], GRAPH_DATA);
+            draw_line_segment_mut(&mut img, (w[0].0, w[0].1 + 1.0), (w[1].0, w[1].1 + 1.0), GRAPH_DATA);
+        }
+        // Individual readings only stay legible as dots for short series
+        if points.len() <= GRAPH_W as usize / 6 {
+            for (x, y) in &points {
+                draw_filled_circle_mut(&mut img, (x.round() as i32, y.round() as i32), 3, GRAPH_DATA);
+            }
+        }
+    }
+
+    img.save(path).map_err(|e| format!("save {}: {e}", path.display()))
+}
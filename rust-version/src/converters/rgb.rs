@@ -1,64 +1,100 @@
 use std::io::Write;
 use std::path::Path;
-use super::{shared, CancelFlag, ProgressTx};
+use super::color::{ColorSpace, Gradient};
+use super::interp::Interpolation;
+use super::palette::{self, PaletteStyle};
+use super::rgb_data::RgbDataset;
+use super::rgb_layout::{FrameRenderer, FrameState, RgbLayout};
+use super::shared::{DataSpan, Timeline};
+use super::{midi, preview::PreviewCapture, shared, CancelFlag, ProgressTx};
 
-fn lerp_color(a: [u8; 3], b: [u8; 3], steps: usize) -> Vec<[u8; 3]> {
-    (0..steps).map(|i| {
-        let t = i as f32 / steps as f32;
-        [
-            (a[0] as f32 + (b[0] as f32 - a[0] as f32) * t) as u8,
-            (a[1] as f32 + (b[1] as f32 - a[1] as f32) * t) as u8,
-            (a[2] as f32 + (b[2] as f32 - a[2] as f32) * t) as u8,
-        ]
-    }).collect()
+#[derive(Clone, Debug)]
+pub struct RgbOptions {
+    pub interpolation: Interpolation,
+    pub space: ColorSpace,
+    pub layout: RgbLayout,
+    /// Output resolution, rounded down to even numbers for yuv420p
+    pub width: u32,
+    pub height: u32,
+    /// Also writes the red, green and blue curves as CC 20-22 in a Standard
+    /// MIDI File, `{name}.mid`
+    pub midi: bool,
+    /// Also writes a swatch image as `{name}_palette.png`/`.svg` and the colours
+    /// as `{name}.gpl` and `{name}.ase` palettes
+    pub palette: PaletteStyle,
+}
+
+impl Default for RgbOptions {
+    fn default() -> Self {
+        Self {
+            interpolation: Interpolation::default(),
+            space: ColorSpace::default(),
+            layout: RgbLayout::default(),
+            width: 520,
+            height: 520,
+            midi: false,
+            palette: PaletteStyle::default(),
+        }
+    }
 }
 
 pub fn convert_rgb(
     file_path: &Path,
     is_folder: bool,
+    options: RgbOptions,
+    timeline: Timeline,
     tx: ProgressTx,
     cancel: CancelFlag,
 ) -> Result<(), String> {
     shared::process_files(file_path, is_folder, tx, cancel.clone(), |pdf, out_dir, name, prog_tx| {
         let out = out_dir.join(format!("{name}.mp4"));
         let partial_out = out.with_extension("tmp.mp4");
-        if out.exists() {
+        let palette_done = options.palette == PaletteStyle::Off || out_dir.join(format!("{name}.gpl")).exists();
+        if out.exists() && palette_done {
             return Ok(());
         }
 
         let text = shared::extract_text(pdf)?;
-        let digits: String = text.chars().filter(|c| c.is_ascii_digit()).collect();
-        let mut colors: Vec<[u8; 3]> = Vec::new();
-        for chunk in digits.as_bytes().chunks(9) {
-            if chunk.len() == 9 {
-                let s = std::str::from_utf8(chunk).unwrap_or("");
-                let r = s[0..3].parse::<u8>().unwrap_or(0);
-                let g = s[3..6].parse::<u8>().unwrap_or(0);
-                let b = s[6..9].parse::<u8>().unwrap_or(0);
-                colors.push([r, g, b]);
-            }
+        let data = RgbDataset::parse(&text);
+        let report = out_dir.join(format!("{name}_report.txt"));
+        std::fs::write(&report, data.report(name)).map_err(|e| format!("write {}: {e}", report.display()))?;
+        if !data.malformed.is_empty() {
+            let _ = prog_tx.send(super::Progress::Warning {
+                name: name.to_string(),
+                message: format!("{} malformed entries in the RGB data, see the _report.txt", data.malformed.len()),
+            });
         }
+        let colors = data.colors();
         if colors.is_empty() { return Err("No RGB color data found".into()); }
 
-        let mut interpolated: Vec<[u8; 3]> = Vec::new();
-        for w in colors.windows(2) {
-            if cancel.load(std::sync::atomic::Ordering::Relaxed) {
-                return Err("Cancelled.".into());
-            }
-            interpolated.extend(lerp_color(w[0], w[1], 3000));
+        if !palette_done {
+            palette::write(&data, options.palette, out_dir, name)?;
+        }
+        if out.exists() {
+            return Ok(());
         }
 
-        // Switched from 25 to 24 fps (720 seconds duration remains unchanged)
-        let num_frames = 24 * 720;
-        let gradient: Vec<[u8; 3]> = (0..num_frames).map(|i| {
-            let idx = (i * interpolated.len()) / num_frames;
-            interpolated[idx.min(interpolated.len() - 1)]
-        }).collect();
+        let days = data.days();
+        let duration = timeline.checked_duration_secs(DataSpan {
+            readings: colors.len(),
+            days,
+            real_secs: days as f64 * 86400.0,
+        }, prog_tx, name);
+        let num_frames = ((duration * 24.0).round() as usize).max(1);
+
+        let path = Gradient::new(&colors, options.space, options.interpolation);
+        let last_frame = num_frames.saturating_sub(1).max(1) as f64;
+        let gradient: Vec<[u8; 3]> = (0..num_frames).map(|i| path.at(i as f64 / last_frame)).collect();
+
+        let width = options.width.clamp(16, 3840) & !1;
+        let height = options.height.clamp(16, 2160) & !1;
+        let day_of_entry = data.day_of_entry();
+        let renderer = FrameRenderer::new(options.layout, (width, height), &path, &colors, &day_of_entry);
 
         let mut args: Vec<String> = vec![
             "-y".into(), "-hide_banner".into(), "-loglevel".into(), "error".into(),
             "-f".into(), "rawvideo".into(), "-pix_fmt".into(), "rgb24".into(),
-            "-s".into(), "520x520".into(), "-r".into(), "24".into(),
+            "-s".into(), format!("{width}x{height}"), "-r".into(), "24".into(),
             "-i".into(), "pipe:0".into(), "-c:v".into(), "libx264".into(),
             "-preset".into(), shared::ffmpeg_preset(), "-pix_fmt".into(), "yuv420p".into(),
         ];
@@ -70,17 +106,24 @@ pub fn convert_rgb(
         
         args.push(partial_out.to_string_lossy().to_string());
 
+        let mut preview = PreviewCapture::new(width, height, num_frames, 24.0);
+
         let result = shared::run_ffmpeg_stream(&args, prog_tx, name, cancel.clone(), |stdin| {
-            let mut raw = vec![0u8; 520 * 520 * 3];
+            let mut raw = vec![0u8; width as usize * height as usize * 3];
             let mut count = 0;
+            let mut shown: Option<FrameState> = None;
             for color in &gradient {
                 if cancel.load(std::sync::atomic::Ordering::Relaxed) {
                     return Err("Cancelled.".into());
                 }
 
-                for px in raw.chunks_mut(3) {
-                    px[0] = color[0]; px[1] = color[1]; px[2] = color[2];
+                // A held colour repeats the last frame as it is, only changes are drawn
+                let state = renderer.state(count as f64 / last_frame, *color);
+                if shown.as_ref() != Some(&state) {
+                    renderer.draw(&state, &mut raw);
+                    shown = Some(state);
                 }
+                preview.offer(count, &raw);
                 if stdin.write_all(&raw).is_err() { break; } 
                 
                 count += 1;
@@ -96,6 +139,19 @@ pub fn convert_rgb(
         
         if result.is_ok() && !cancel.load(std::sync::atomic::Ordering::Relaxed) {
             let _ = std::fs::rename(&partial_out, &out);
+            preview.write(&out, prog_tx, name);
+            if options.midi {
+                let controllers: Vec<midi::Controller> = [(20, "Red"), (21, "Green"), (22, "Blue")]
+                    .into_iter()
+                    .enumerate()
+                    .map(|(c, (number, label))| {
+                        midi::Controller::new(number, label, duration, |p| path.at(p)[c] as f32 / 255.0)
+                    })
+                    .collect();
+                if let Err(e) = midi::write_curves(&out_dir.join(format!("{name}.mid")), &controllers) {
+                    let _ = prog_tx.send(super::Progress::Warning { name: name.to_string(), message: format!("MIDI export: {e}") });
+                }
+            }
         } else {
             let _ = std::fs::remove_file(&partial_out);
         }
@@ -0,0 +1,236 @@
+#[derive(Clone, Debug)]
+pub struct RgbEntry {
+    /// Day label as printed at the start of the line, e.g. `01`
+    pub label: Option<String>,
+    /// Line of the extracted text, 1-based
+    pub line: usize,
+    pub color: [u8; 3],
+}
+
+#[derive(Clone, Debug)]
+pub struct Malformed {
+    pub line: usize,
+    /// The entry as printed
+    pub text: String,
+    pub reason: String,
+}
+
+/// Colours as printed, one or more per line after an optional day label:
+/// `01 200, 200, 000`, `200 200 0`, `200200000` or `#C8C800`.
+#[derive(Clone, Debug, Default)]
+pub struct RgbDataset {
+    pub entries: Vec<RgbEntry>,
+    pub malformed: Vec<Malformed>,
+}
+
+/// One component slot of a triplet, read or with what went wrong.
+struct Slot<'a> {
+    text: &'a str,
+    value: Result<u8, String>,
+}
+
+fn component(text: &str) -> Slot<'_> {
+    let value = match text.parse::<u32>() {
+        Ok(v) if v <= 255 => Ok(v as u8),
+        Ok(v) => Err(format!("{v} is above 255")),
+        Err(_) => Err(format!("'{text}' is not a number")),
+    };
+    Slot { text, value }
+}
+
+/// `#RRGGBB`, or the `#RGB` shorthand.
+fn parse_hex(token: &str) -> Option<[u8; 3]> {
+    let hex = token.strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |i: usize, len: usize| u8::from_str_radix(&hex[i * len..(i + 1) * len], 16).ok();
+    match hex.len() {
+        6 => Some([digit(0, 2)?, digit(1, 2)?, digit(2, 2)?]),
+        3 => Some([digit(0, 1)? * 17, digit(1, 1)? * 17, digit(2, 1)? * 17]),
+        _ => None,
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || c == ',' || c == ';'
+}
+
+/// Garbled entries only hold stray letters, words are two or more.
+fn is_word(token: &str) -> bool {
+    token.len() >= 2 && token.chars().all(|c| c.is_alphabetic())
+}
+
+/// Component slots a token fills: a zero-padded 9-digit group fills three.
+fn slot_count(token: &str) -> usize {
+    if token.len() == 9 && token.chars().all(|c| c.is_ascii_digit()) { 3 } else { 1 }
+}
+
+impl RgbDataset {
+    pub fn parse(text: &str) -> Self {
+        let mut data = RgbDataset::default();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let trimmed = line.trim();
+            let mut malformed = |text: &str, reason: String| {
+                data.malformed.push(Malformed { line: line_no, text: text.to_string(), reason });
+            };
+
+            // Leading words name the day, as in `Jan 01 #C8C800` or `Day 01 200 200 000`
+            let mut body = trimmed.trim_start_matches(is_separator);
+            let mut words: Vec<&str> = Vec::new();
+            while let Some(word) = body.split(is_separator).next().filter(|t| is_word(t)) {
+                words.push(word);
+                body = body[word.len()..].trim_start_matches(is_separator);
+            }
+
+            let tokens: Vec<&str> = body.split(is_separator).filter(|t| !t.is_empty()).collect();
+            let has_hex = tokens.iter().any(|t| t.len() > 1 && t.starts_with('#'));
+            if !has_hex && !body.chars().any(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            let stray_word = tokens.iter().find(|t| is_word(t));
+            if !has_hex && (!words.is_empty() || stray_word.is_some()) {
+                // Headings like `RGB LOG 2023` or `Page 3 of 10` carry too few numbers for a colour
+                let numbers: usize = tokens.iter().filter(|t| t.chars().all(|c| c.is_ascii_digit())).map(|t| slot_count(t)).sum();
+                if numbers < 3 {
+                    continue;
+                }
+            }
+            if let Some(word) = stray_word {
+                malformed(trimmed, format!("unexpected word '{word}'"));
+                continue;
+            }
+
+            // A leading short number is the day label when it is marked as one,
+            // or when the rest of the line only adds up to whole triplets without it
+            let label = tokens.first().and_then(|first| {
+                let bare = first.trim_end_matches(['.', ':']);
+                let numeric = !bare.is_empty() && bare.len() <= 3 && bare.chars().all(|c| c.is_ascii_digit());
+                let followed_by_space = body[first.len()..].starts_with(char::is_whitespace);
+                if !numeric || !followed_by_space || tokens.len() < 2 {
+                    return None;
+                }
+                let slots: usize = tokens.iter().filter(|t| !t.starts_with('#')).map(|t| slot_count(t)).sum();
+                (bare.len() < first.len() || slots % 3 == 1).then(|| bare.to_string())
+            });
+            let rest = &tokens[label.is_some() as usize..];
+            let label = match (words.is_empty(), label) {
+                (true, label) => label,
+                (false, Some(label)) => Some(format!("{} {label}", words.join(" "))),
+                (false, None) => Some(words.join(" ")),
+            };
+
+            let mut slots: Vec<Slot> = Vec::new();
+            let mut colors: Vec<[u8; 3]> = Vec::new();
+            let mut broken = false;
+            for token in rest {
+                if token.starts_with('#') {
+                    match parse_hex(token) {
+                        Some(color) => colors.push(color),
+                        None => malformed(token, "not a #RRGGBB colour".into()),
+                    }
+                } else if slot_count(token) == 3 {
+                    slots.extend((0..3).map(|i| component(&token[i * 3..i * 3 + 3])));
+                } else if token.len() > 3 && token.chars().all(|c| c.is_ascii_digit()) {
+                    // A lost leading zero would shift every component after it
+                    malformed(token, format!("group of {} digits, expected 9", token.len()));
+                    broken = true;
+                } else {
+                    slots.push(component(token));
+                }
+            }
+
+            if broken || !slots.len().is_multiple_of(3) {
+                if !slots.is_empty() && !broken {
+                    let text = rest.iter().filter(|t| !t.starts_with('#')).cloned().collect::<Vec<_>>().join(" ");
+                    malformed(&text, format!("{} components, expected groups of three", slots.len()));
+                }
+                slots.clear();
+            }
+            for triplet in slots.chunks(3) {
+                match (&triplet[0].value, &triplet[1].value, &triplet[2].value) {
+                    (Ok(r), Ok(g), Ok(b)) => colors.push([*r, *g, *b]),
+                    _ => {
+                        let reason = triplet.iter().find_map(|s| s.value.clone().err()).unwrap_or_default();
+                        let text = triplet.iter().map(|s| s.text).collect::<Vec<_>>().join(", ");
+                        malformed(&text, reason);
+                    }
+                }
+            }
+
+            data.entries.extend(colors.into_iter().map(|color| RgbEntry { label: label.clone(), line: line_no, color }));
+        }
+        data
+    }
+
+    pub fn colors(&self) -> Vec<[u8; 3]> {
+        self.entries.iter().map(|e| e.color).collect()
+    }
+
+    /// Day of every entry: colours sharing a label share a day, unlabelled
+    /// ones get a day each.
+    pub fn day_of_entry(&self) -> Vec<usize> {
+        let mut days = Vec::with_capacity(self.entries.len());
+        let mut day = 0;
+        let mut previous: Option<&str> = None;
+        for (i, entry) in self.entries.iter().enumerate() {
+            let label = entry.label.as_deref();
+            if i > 0 && (label.is_none() || label != previous) {
+                day += 1;
+            }
+            days.push(day);
+            previous = label;
+        }
+        days
+    }
+
+    /// Days covered.
+    pub fn days(&self) -> usize {
+        self.day_of_entry().last().map_or(0, |d| d + 1)
+    }
+
+    /// Plain-text summary of what was read and every malformed entry with its line.
+    pub fn report(&self, source: &str) -> String {
+        let mut out = format!(
+            "RGB data report for {source}\n{} colours, {} malformed entries\n",
+            self.entries.len(),
+            self.malformed.len()
+        );
+        if !self.entries.is_empty() {
+            out.push_str("\nColours:\n");
+            for e in &self.entries {
+                let label = e.label.as_deref().unwrap_or("-");
+                let [r, g, b] = e.color;
+                out.push_str(&format!("  {label:>6}  line {:>4}  #{r:02X}{g:02X}{b:02X}\n", e.line));
+            }
+        }
+        if !self.malformed.is_empty() {
+            out.push_str("\nMalformed:\n");
+            for m in &self.malformed {
+                out.push_str(&format!("  line {:>4}  {:<16}  {}\n", m.line, m.text, m.reason));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separator_led_lines_keep_their_label() {
+        let data = RgbDataset::parse(", 01 200 200 000\n;02 #C8C800\n");
+        let parsed: Vec<_> = data.entries.iter().map(|e| (e.label.as_deref(), e.color)).collect();
+        assert_eq!(parsed, [(Some("01"), [200, 200, 0]), (Some("02"), [200, 200, 0])]);
+        assert!(data.malformed.is_empty());
+    }
+
+    #[test]
+    fn separator_before_multibyte_text_does_not_panic() {
+        let data = RgbDataset::parse(",° 12 200 200 000\n");
+        assert!(data.entries.iter().all(|e| e.label.as_deref() != Some("°")));
+    }
+}
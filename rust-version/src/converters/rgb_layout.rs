@@ -0,0 +1,240 @@
+use super::color::Gradient;
+
+const BACKGROUND: [u8; 3] = [0, 0, 0];
+
+/// Stripes across the frame in the barcode layout.
+const BARCODE_STRIPES: f32 = 24.0;
+/// Rings in the concentric squares layout, the centre one being the current colour.
+const RINGS: usize = 8;
+/// Entries each ring lags behind the one inside it.
+const RING_LAG: f64 = 0.5;
+/// Share of the frame height taken by the timeline bar.
+const BAR_SHARE: f32 = 0.15;
+/// Brightness of the grid cells other than the current day.
+const GRID_DIM: f32 = 0.35;
+
+/// How the colours are laid out in each frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RgbLayout {
+    /// The current colour over the whole frame
+    #[default]
+    Solid,
+    /// Every entry as a stripe, scrolling past a marker in the middle
+    Barcode,
+    /// One cell per day, the current day lit and the rest dimmed
+    DayGrid,
+    /// Nested squares, the current colour in the middle and older ones outside
+    Squares,
+    /// The current colour above a bar of the whole series with a playhead
+    TimelineBar,
+}
+
+/// What one frame shows: frames with equal states are identical, so a held
+/// colour only has to be drawn once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameState {
+    current: [u8; 3],
+    /// Barcode scroll in pixels, lit day of the grid or playhead column
+    position: usize,
+    /// Colours of the squares, outermost first
+    rings: Vec<[u8; 3]>,
+}
+
+/// Draws the frames of one RGB video.
+pub struct FrameRenderer<'a> {
+    layout: RgbLayout,
+    width: usize,
+    height: usize,
+    gradient: &'a Gradient,
+    /// Parsed entries in order
+    colors: &'a [[u8; 3]],
+    /// Day of every entry
+    day_of_entry: &'a [usize],
+    /// First colour of every day
+    day_colors: Vec<[u8; 3]>,
+    /// The bar of the timeline layout as one row of pixels
+    bar: Vec<u8>,
+    /// Ring of every column and row in the squares layout
+    col_ring: Vec<usize>,
+    row_ring: Vec<usize>,
+}
+
+fn invert(c: [u8; 3]) -> [u8; 3] {
+    // Mid greys would vanish against their inverse
+    let luma = 0.299 * c[0] as f32 + 0.587 * c[1] as f32 + 0.114 * c[2] as f32;
+    if luma > 128.0 { [0, 0, 0] } else { [255, 255, 255] }
+}
+
+fn dim(c: [u8; 3], amount: f32) -> [u8; 3] {
+    c.map(|v| (v as f32 * amount) as u8)
+}
+
+/// Repeats the first `filled` bytes of `buf` through the rest of it, doubling
+/// the copied span each pass.
+fn repeat(buf: &mut [u8], mut filled: usize) {
+    while filled < buf.len() {
+        let n = filled.min(buf.len() - filled);
+        buf.copy_within(..n, filled);
+        filled += n;
+    }
+}
+
+fn fill(buf: &mut [u8], c: [u8; 3]) {
+    if buf.len() >= 3 {
+        buf[..3].copy_from_slice(&c);
+        repeat(buf, 3);
+    }
+}
+
+/// Ring of a pixel `inset` pixels in from the nearest edge.
+fn ring_at(inset: usize, half: f32) -> usize {
+    ((inset as f32 / half * RINGS as f32) as usize).min(RINGS - 1)
+}
+
+impl<'a> FrameRenderer<'a> {
+    pub fn new(
+        layout: RgbLayout,
+        (width, height): (u32, u32),
+        gradient: &'a Gradient,
+        colors: &'a [[u8; 3]],
+        day_of_entry: &'a [usize],
+    ) -> Self {
+        let (width, height) = (width as usize, height as usize);
+        let mut day_colors: Vec<[u8; 3]> = Vec::new();
+        for (color, day) in colors.iter().zip(day_of_entry) {
+            if *day >= day_colors.len() {
+                day_colors.push(*color);
+            }
+        }
+        let last = (width - 1).max(1) as f64;
+        let bar = if layout == RgbLayout::TimelineBar {
+            (0..width).flat_map(|x| gradient.at(x as f64 / last)).collect()
+        } else {
+            Vec::new()
+        };
+        let (col_ring, row_ring) = if layout == RgbLayout::Squares {
+            let half = (width.min(height) as f32 / 2.0).max(1.0);
+            (
+                (0..width).map(|x| ring_at(x.min(width - 1 - x), half)).collect(),
+                (0..height).map(|y| ring_at(y.min(height - 1 - y), half)).collect(),
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        Self { layout, width, height, gradient, colors, day_of_entry, day_colors, bar, col_ring, row_ring }
+    }
+
+    /// Width of one barcode stripe in pixels.
+    fn stripe(&self) -> usize {
+        ((self.width as f32 / BARCODE_STRIPES) as usize).max(2)
+    }
+
+    /// State of the frame at `progress` through the series, `current` being the
+    /// gradient's colour there.
+    pub fn state(&self, progress: f64, current: [u8; 3]) -> FrameState {
+        let exact = progress * self.colors.len().saturating_sub(1) as f64;
+        let mut state = FrameState { current, position: 0, rings: Vec::new() };
+        match self.layout {
+            RgbLayout::Solid => {}
+            RgbLayout::Barcode => state.position = (exact * self.stripe() as f64).round() as usize,
+            RgbLayout::DayGrid => {
+                state.position = self.day_of_entry.get(exact.round() as usize).copied().unwrap_or(0);
+            }
+            RgbLayout::Squares => {
+                let step = RING_LAG / self.colors.len().saturating_sub(1).max(1) as f64;
+                state.rings = (0..RINGS)
+                    .map(|k| if k == RINGS - 1 { current } else { self.gradient.at(progress - (RINGS - 1 - k) as f64 * step) })
+                    .collect();
+            }
+            RgbLayout::TimelineBar => state.position = (progress * (self.width - 1) as f64).round() as usize,
+        }
+        state
+    }
+
+    /// Draws `state` into `raw`, whole rows and runs at a time.
+    pub fn draw(&self, state: &FrameState, raw: &mut [u8]) {
+        let (w, h) = (self.width, self.height);
+        let row = w * 3;
+        let current = state.current;
+
+        match self.layout {
+            RgbLayout::Solid => fill(raw, current),
+            RgbLayout::Barcode => {
+                // Every row is the same stripes, only the marker ticks differ
+                let stripe = self.stripe() as i64;
+                let start = state.position as i64 - (w / 2) as i64 + stripe / 2;
+                let mut x = 0;
+                while x < w {
+                    let k = (start + x as i64).div_euclid(stripe);
+                    let end = ((k + 1) * stripe - start).clamp(x as i64 + 1, w as i64) as usize;
+                    let c = usize::try_from(k).ok().and_then(|k| self.colors.get(k)).copied().unwrap_or(BACKGROUND);
+                    fill(&mut raw[x * 3..end * 3], c);
+                    x = end;
+                }
+                repeat(raw, row);
+
+                let marker = invert(current);
+                let tick = h / 12;
+                for y in (0..tick).chain(h - tick..h) {
+                    fill(&mut raw[y * row + (w / 2 - 1) * 3..y * row + (w / 2 + 2) * 3], marker);
+                }
+            }
+            RgbLayout::DayGrid => {
+                let days = self.day_colors.len().max(1);
+                let cols = (days as f64).sqrt().ceil() as usize;
+                let rows = days.div_ceil(cols);
+                let cell = (w / cols).min(h / rows).max(1);
+                let (left, top) = ((w - cell * cols) / 2, (h - cell * rows) / 2);
+                let gap = (cell / 16).max(1);
+                let span = cell.saturating_sub(2 * gap);
+
+                fill(raw, BACKGROUND);
+                let mut edge = vec![0u8; span * 3];
+                let mut inner = vec![0u8; span * 3];
+                for (day, color) in self.day_colors.iter().enumerate() {
+                    let lit = day == state.position;
+                    if lit {
+                        let border = invert(current);
+                        fill(&mut edge, border);
+                        fill(&mut inner, border);
+                        let band = (2 * gap).min(span / 2);
+                        fill(&mut inner[band * 3..(span - band) * 3], current);
+                    } else {
+                        let c = dim(*color, GRID_DIM);
+                        fill(&mut edge, c);
+                        fill(&mut inner, c);
+                    }
+
+                    let (cx, cy) = (left + (day % cols) * cell + gap, top + (day / cols) * cell + gap);
+                    for dy in 0..span {
+                        let is_edge = dy < 2 * gap || dy >= span.saturating_sub(2 * gap);
+                        let at = (cy + dy) * row + cx * 3;
+                        raw[at..at + span * 3].copy_from_slice(if is_edge { &edge } else { &inner });
+                    }
+                }
+            }
+            RgbLayout::Squares => {
+                // A pixel's ring is the smaller of its column's and its row's, so
+                // one row per ring covers the whole frame
+                let patterns: Vec<Vec<u8>> = (0..RINGS)
+                    .map(|r| self.col_ring.iter().flat_map(|c| state.rings[(*c).min(r)]).collect())
+                    .collect();
+                for (y, r) in self.row_ring.iter().enumerate() {
+                    raw[y * row..(y + 1) * row].copy_from_slice(&patterns[*r]);
+                }
+            }
+            RgbLayout::TimelineBar => {
+                let bar_h = ((h as f32 * BAR_SHARE) as usize).max(4);
+                let split = h - bar_h;
+                fill(&mut raw[..split * row], current);
+                fill(&mut raw[split * row..(split + 2) * row], BACKGROUND);
+
+                let bar = &mut raw[(split + 2) * row..];
+                bar[..row].copy_from_slice(&self.bar);
+                let (from, to) = (state.position.saturating_sub(1), (state.position + 2).min(w));
+                fill(&mut bar[from * 3..to * 3], invert(current));
+                repeat(bar, row);
+            }
+        }
+    }
+}
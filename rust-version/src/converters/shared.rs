@@ -1,9 +1,10 @@
 use rayon::prelude::*;
+use std::cell::Cell;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::atomic::{Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Condvar, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::time::Duration;
 
@@ -32,10 +33,252 @@ pub fn pdf_render_dpi() -> u32 {
     std::env::var("CUBE_PDF_DPI")
         .ok()
         .and_then(|v| v.parse::<u32>().ok())
-        .filter(|&v| v >= 50 && v <= 600)
+        .filter(|v| (50..=600).contains(v))
         .unwrap_or(120)
 }
 
+/// Integrated loudness every audio output is normalized to (`CUBE_TARGET_LUFS`).
+pub fn target_loudness() -> f64 {
+    std::env::var("CUBE_TARGET_LUFS")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|v| (-40.0..=-5.0).contains(v))
+        .unwrap_or(-16.0)
+}
+
+/// True-peak ceiling in dBTP applied after normalization (`CUBE_TRUE_PEAK`).
+pub fn true_peak_ceiling() -> f64 {
+    std::env::var("CUBE_TRUE_PEAK")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|v| (-12.0..=0.0).contains(v))
+        .unwrap_or(-1.0)
+}
+
+/// Memory the audio renders running at once may hold, in MiB (`CUBE_RENDER_MEMORY_MB`).
+pub fn render_memory_budget() -> u64 {
+    std::env::var("CUBE_RENDER_MEMORY_MB")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|&v| v >= 64)
+        .unwrap_or(2048)
+        * 1024
+        * 1024
+}
+
+/// Poster frame position from `CUBE_POSTER_AT`: `"40%"` of the video, `"12.5s"` or a
+/// plain frame number. Defaults to the middle of the video.
+pub fn poster_position() -> PosterAt {
+    let raw = std::env::var("CUBE_POSTER_AT").unwrap_or_default();
+    let raw = raw.trim();
+    if let Some(pct) = raw.strip_suffix('%') {
+        if let Ok(v) = pct.trim().parse::<f32>() {
+            return PosterAt::Fraction((v / 100.0).clamp(0.0, 1.0));
+        }
+    } else if let Some(secs) = raw.strip_suffix('s') {
+        if let Ok(v) = secs.trim().parse::<f32>() {
+            return PosterAt::Seconds(v.max(0.0));
+        }
+    } else if let Ok(v) = raw.parse::<usize>() {
+        return PosterAt::Frame(v);
+    }
+    PosterAt::Fraction(0.5)
+}
+
+/// Number of evenly spaced frames on the contact sheet (`CUBE_THUMBS`, 0 disables it).
+pub fn thumbnail_count() -> usize {
+    std::env::var("CUBE_THUMBS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&v| v <= 64)
+        .unwrap_or(8)
+}
+
+/// Image format for posters and contact sheets (`CUBE_POSTER_FORMAT`, "png" or "jpg").
+pub fn poster_extension() -> &'static str {
+    match std::env::var("CUBE_POSTER_FORMAT").unwrap_or_default().to_lowercase().as_str() {
+        "jpg" | "jpeg" => "jpg",
+        _ => "png",
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum PosterAt {
+    Frame(usize),
+    Seconds(f32),
+    Fraction(f32),
+}
+
+/// Longest output a timeline may ask for. The audio converters render the whole
+/// file in memory before encoding, so a month at a minute per reading must stop.
+pub const MAX_TIMELINE_SECS: f64 = 60.0 * 60.0;
+
+/// How much a converter's input covers, for timelines derived from the data.
+#[derive(Clone, Copy, Debug)]
+pub struct DataSpan {
+    pub readings: usize,
+    pub days: usize,
+    /// Real time the records cover, in seconds
+    pub real_secs: f64,
+}
+
+/// How long an output plays for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Timeline {
+    /// Stretches any amount of data to this many seconds
+    Fixed(f32),
+    /// Seconds of output for every reading
+    PerReading(f32),
+    /// Seconds of output for every day of records
+    PerDay(f32),
+    /// Real time divided by this ratio, 1440 turns a day into a minute
+    Compressed(f32),
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Timeline::Fixed(12.0 * 60.0)
+    }
+}
+
+impl Timeline {
+    /// Length the data asks for, before the output limits.
+    fn requested_secs(self, span: DataSpan) -> f64 {
+        match self {
+            Timeline::Fixed(secs) => secs as f64,
+            Timeline::PerReading(secs) => secs as f64 * span.readings as f64,
+            Timeline::PerDay(secs) => secs as f64 * span.days.max(1) as f64,
+            Timeline::Compressed(ratio) => span.real_secs / (ratio as f64).max(1e-3),
+        }
+    }
+
+    pub fn duration_secs(self, span: DataSpan) -> f64 {
+        self.requested_secs(span).clamp(1.0, MAX_TIMELINE_SECS)
+    }
+
+    /// `duration_secs`, with a warning for `name` when the data asked for more
+    /// than the longest output and was cut to it.
+    pub fn checked_duration_secs(self, span: DataSpan, tx: &ProgressTx, name: &str) -> f64 {
+        let asked = self.requested_secs(span);
+        if asked > MAX_TIMELINE_SECS {
+            let _ = tx.send(Progress::Warning {
+                name: name.to_string(),
+                message: format!("timeline asks for {asked:.0} s, capped at {MAX_TIMELINE_SECS:.0} s"),
+            });
+        }
+        self.duration_secs(span)
+    }
+}
+
+/// Bytes held by the audio renders in flight, shared by every converter.
+static RENDER_MEMORY: Mutex<u64> = Mutex::new(0);
+static RENDER_MEMORY_FREED: Condvar = Condvar::new();
+
+/// Buffers a render keeps per output sample: the samples themselves and the
+/// loudness and true-peak passes over them.
+const RENDER_COPIES: u64 = 3;
+
+thread_local! {
+    /// Set while a `MemoryScope` on this thread already holds the share of the
+    /// renders it runs.
+    static RENDER_COVERED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A render's share of the memory budget, given back when it is dropped. Files
+/// convert in parallel, so long timelines wait here for room instead of all
+/// allocating at once.
+#[derive(Debug)]
+pub struct MemoryLease(u64);
+
+impl MemoryLease {
+    /// Waits until `samples` f32 samples fit in the budget next to the renders
+    /// already running. A render larger than the whole budget still runs, alone.
+    pub fn reserve(samples: usize, cancel: &CancelFlag) -> Result<Self, String> {
+        if RENDER_COVERED.with(Cell::get) {
+            return Ok(Self(0));
+        }
+        let bytes = samples as u64 * std::mem::size_of::<f32>() as u64 * RENDER_COPIES;
+        let budget = render_memory_budget();
+        let mut used = RENDER_MEMORY.lock().unwrap_or_else(|e| e.into_inner());
+        while *used > 0 && *used + bytes > budget {
+            if cancel.load(Ordering::Relaxed) {
+                return Err("Cancelled.".into());
+            }
+            used = RENDER_MEMORY_FREED
+                .wait_timeout(used, Duration::from_millis(200))
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        *used += bytes;
+        Ok(Self(bytes))
+    }
+}
+
+impl Drop for MemoryLease {
+    fn drop(&mut self) {
+        let mut used = RENDER_MEMORY.lock().unwrap_or_else(|e| e.into_inner());
+        *used -= self.0;
+        RENDER_MEMORY_FREED.notify_all();
+    }
+}
+
+/// One lease for a job that renders several buffers in turn and keeps them
+/// together, like a mix. Renders it runs on this thread draw on it instead of
+/// reserving their own, which could wait forever on the share held here.
+#[derive(Debug)]
+pub struct MemoryScope {
+    _lease: MemoryLease,
+}
+
+impl MemoryScope {
+    /// Waits for room for `samples` f32 samples, the most the job holds at once.
+    pub fn reserve(samples: usize, cancel: &CancelFlag) -> Result<Self, String> {
+        let lease = MemoryLease::reserve(samples, cancel)?;
+        RENDER_COVERED.with(|c| c.set(true));
+        Ok(Self { _lease: lease })
+    }
+}
+
+impl Drop for MemoryScope {
+    fn drop(&mut self) {
+        RENDER_COVERED.with(|c| c.set(false));
+    }
+}
+
+/// Small seeded xorshift generator so randomized output can be reproduced.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // splitmix64 scrambles the seed so small seeds still give varied streams
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        Self((z ^ (z >> 31)).max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// Uniform in 0..1
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// Stable FNV-1a hash of a file name, mixed into seeds so each file of a folder
+/// gets its own random stream while reruns stay reproducible.
+pub fn name_seed(name: &str) -> u64 {
+    name.bytes().fold(0xCBF2_9CE4_8422_2325, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01B3))
+}
+
 pub fn extract_text(pdf_path: &Path) -> Result<String, String> {
     let program = pdftotext_bin();
     let mut cmd = Command::new(&program);
@@ -1,7 +1,26 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use super::{shared, CancelFlag, ProgressTx};
+use image::{imageops, RgbImage};
+
+use super::{preview::PreviewCapture, shared, CancelFlag, ProgressTx};
+
+const FRAME_W: u32 = 1920;
+const FRAME_H: u32 = 1080;
+/// Four seconds per image at 24 fps.
+const FRAMES_PER_IMAGE: usize = 4 * 24;
+
+/// An image as the scale and pad filters place it: shrunk to fit, never
+/// enlarged, centred on black.
+fn letterbox(img: &RgbImage) -> RgbImage {
+    let fit = (FRAME_W as f32 / img.width().max(1) as f32).min(FRAME_H as f32 / img.height().max(1) as f32).min(1.0);
+    let w = ((img.width() as f32 * fit) as u32).max(1);
+    let h = ((img.height() as f32 * fit) as u32).max(1);
+    let scaled = imageops::resize(img, w, h, imageops::FilterType::Triangle);
+    let mut frame = RgbImage::new(FRAME_W, FRAME_H);
+    imageops::replace(&mut frame, &scaled, ((FRAME_W - w) / 2) as i64, ((FRAME_H - h) / 2) as i64);
+    frame
+}
 
 pub fn convert_slideshow(
     folder_path: &Path,
@@ -61,7 +80,7 @@ pub fn convert_slideshow(
     fs::write(&concat_file, concat_content).map_err(|e| e.to_string())?;
 
     // Switched to 24 fps (4 seconds per image remains exactly the same real-time duration)
-    let total_frames = files.len() * 4 * 24; 
+    let total_frames = files.len() * FRAMES_PER_IMAGE;
     
     let filter = "scale='min(1920,iw)':'min(1080,ih)':force_original_aspect_ratio=decrease,scale=trunc(iw/2)*2:trunc(ih/2)*2,pad=1920:1080:(1920-iw)/2:(1080-ih)/2,format=yuv420p";
 
@@ -85,8 +104,17 @@ pub fn convert_slideshow(
     let _ = fs::remove_dir_all(&tmp_dir);
 
     if result.is_ok() && !cancel.load(std::sync::atomic::Ordering::Relaxed) {
-        let _ = tx.send(super::Progress::Done { name: stem });
+        let mut preview = PreviewCapture::new(FRAME_W, FRAME_H, total_frames, 24.0);
+        let wanted: Vec<usize> = (0..total_frames).filter(|f| preview.wants(*f)).collect();
+        for frame in wanted {
+            let file = &files[(frame / FRAMES_PER_IMAGE).min(files.len() - 1)];
+            if let Ok(img) = image::open(file) {
+                preview.offer_image(frame, letterbox(&img.to_rgb8()));
+            }
+        }
         let _ = fs::rename(&partial_out, &out);
+        preview.write(&out, &tx, &stem);
+        let _ = tx.send(super::Progress::Done { name: stem });
     } else {
         let _ = fs::remove_file(&partial_out);
     }
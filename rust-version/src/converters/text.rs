@@ -1,7 +1,9 @@
 use std::fs;
 use std::path::Path;
+use image::{Rgb, RgbImage};
+use imageproc::drawing::draw_text_mut;
 use rusttype::{Font, Scale};
-use super::{shared, CancelFlag, ProgressTx};
+use super::{preview::PreviewCapture, shared, CancelFlag, ProgressTx};
 
 pub fn convert_text(
     file_path: &Path,
@@ -126,6 +128,19 @@ pub fn convert_text(
         
         if result.is_ok() && !cancel.load(std::sync::atomic::Ordering::Relaxed) {
             let _ = fs::rename(&partial_out, &out);
+
+            // The same scroll as the drawtext filter, drawn only for the frames the preview keeps
+            let mut preview = PreviewCapture::new(frame_w, frame_h, total_frames, fps);
+            let v_metrics = font.v_metrics(scale);
+            let top = ((frame_h as f32 - (v_metrics.ascent - v_metrics.descent)) / 2.0).round() as i32;
+            let wanted: Vec<usize> = (0..total_frames).filter(|f| preview.wants(*f)).collect();
+            for frame in wanted {
+                let mut img = RgbImage::new(frame_w, frame_h);
+                let x = frame_w as i64 - (frame as u32 * speed_px_per_frame) as i64;
+                draw_text_mut(&mut img, Rgb(color), x as i32, top, scale, &font, &text);
+                preview.offer_image(frame, img);
+            }
+            preview.write(&out, prog_tx, name);
         } else {
             let _ = fs::remove_file(&partial_out);
         }
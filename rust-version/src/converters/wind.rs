@@ -1,127 +1,785 @@
 use std::fs;
 use std::path::Path;
 
-use super::{shared, CancelFlag, ProgressTx};
+use super::audio::{self, LoopPlayer, LoopSettings};
+use super::dsp::{HighShelf, PinkNoise, Smoother, Svf};
+use super::gust::{self, Accents, GustSettings};
+use super::interp::{Interpolation, Series};
+use super::shared::{DataSpan, MemoryLease, Rng, Timeline};
+use super::wind_data::WindDataset;
+use super::{loudness, midi, preview, shared, CancelFlag, ProgressTx};
 
-pub fn convert_wind(
-    file_path: &Path,
-    is_folder: bool,
-    tx: ProgressTx,
-    cancel: CancelFlag,
-) -> Result<(), String> {
-    shared::process_files(file_path, is_folder, tx, cancel.clone(), |pdf, out_dir, name, prog_tx| {
-        let out = out_dir.join(format!("{name}.mp3"));
-        let partial_out = out.with_extension("tmp.mp3");
-        if out.exists() {
-            return Ok(());
+/// Shape of the curve between the bottom and top of the input range.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GainCurve {
+    Linear,
+    Logarithmic,
+    Exponential,
+    /// `(reading, gain)` breakpoints in input units, gain in 0..1
+    Table(Vec<(f32, f32)>),
+}
+
+/// How a wind reading becomes the gain of the wind loop. Records from different
+/// years use different scales (Beaufort, km/h, 0-10), so every part is adjustable.
+#[derive(Clone, Debug)]
+pub struct WindMapping {
+    pub curve: GainCurve,
+    pub input_min: f32,
+    pub input_max: f32,
+    /// Readings at or below this are silent
+    pub silence_threshold: f32,
+    /// Level of the finished render, 1 at the loudness target. Applied after
+    /// normalization, which would otherwise scale any fixed gain back out.
+    pub ceiling: f32,
+}
+
+impl Default for WindMapping {
+    fn default() -> Self {
+        // Matches the original `intensity / 15.0` with calm readings muted
+        Self {
+            curve: GainCurve::Linear,
+            input_min: 0.0,
+            input_max: 15.0,
+            silence_threshold: 1.0,
+            ceiling: 1.0,
         }
+    }
+}
 
-        let text = shared::extract_text(pdf)?;
-        
-        // Flatten all intensities into a single continuous list
-        let mut wind_intensities: Vec<f32> = Vec::new();
-
-        for line in text.split('\n') {
-            let stripped = if line.len() > 2 { &line[2..] } else { line };
-            let day: Vec<f32> = stripped
-                .split(',')
-                .filter_map(|s| s.trim().parse::<f32>().ok())
-                .collect();
-            wind_intensities.extend(day);
+impl WindMapping {
+    /// Position of a reading within the input range, 0..1.
+    pub fn position(&self, intensity: f32) -> f32 {
+        let span = (self.input_max - self.input_min).max(f32::EPSILON);
+        ((intensity - self.input_min) / span).clamp(0.0, 1.0)
+    }
+
+    pub fn gain(&self, intensity: f32) -> f32 {
+        if intensity <= self.silence_threshold {
+            return 0.0;
+        }
+        let t = self.position(intensity);
+        match &self.curve {
+            GainCurve::Linear => t,
+            GainCurve::Logarithmic => (1.0 + 9.0 * t).log10(),
+            GainCurve::Exponential => ((3.0 * t).exp() - 1.0) / (3.0f32.exp() - 1.0),
+            GainCurve::Table(points) => table_lookup(points, intensity),
         }
+    }
+}
 
-        if wind_intensities.is_empty() {
-            return Err("No wind intensity data found".into());
+fn table_lookup(points: &[(f32, f32)], x: f32) -> f32 {
+    match points {
+        [] => 0.0,
+        [only] => only.1,
+        _ => {
+            if x <= points[0].0 {
+                return points[0].1;
+            }
+            for w in points.windows(2) {
+                let (x0, y0) = w[0];
+                let (x1, y1) = w[1];
+                if x <= x1 {
+                    let t = if x1 > x0 { (x - x0) / (x1 - x0) } else { 1.0 };
+                    return y0 + (y1 - y0) * t;
+                }
+            }
+            points[points.len() - 1].1
         }
+    }
+}
+
+/// Parses breakpoints written as `reading:gain` pairs, e.g. `0:0, 3:0.2, 12:1`.
+pub fn parse_gain_table(text: &str) -> Result<Vec<(f32, f32)>, String> {
+    let mut points = Vec::new();
+    for entry in text.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (x, y) = entry
+            .split_once(':')
+            .ok_or_else(|| format!("Gain table entry '{entry}' is not reading:gain"))?;
+        let x = x.trim().parse::<f32>().map_err(|_| format!("Bad reading in gain table entry '{entry}'"))?;
+        let y = y.trim().parse::<f32>().map_err(|_| format!("Bad gain in gain table entry '{entry}'"))?;
+        points.push((x, y.clamp(0.0, 1.0)));
+    }
+    if points.is_empty() {
+        return Err("Gain table is empty".into());
+    }
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Ok(points)
+}
+
+/// Character of a wind recording, from which the bed is crossfaded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindBand {
+    Calm,
+    Breeze,
+    Gusty,
+    Storm,
+}
+
+impl WindBand {
+    const ALL: [WindBand; 4] = [WindBand::Calm, WindBand::Breeze, WindBand::Gusty, WindBand::Storm];
 
-        let wind_path = Path::new("assets/Wind_Loop.wav");
-        if !wind_path.exists() {
-            return Err("assets/Wind_Loop.wav not found".into());
+    fn tag(self) -> &'static str {
+        match self {
+            WindBand::Calm => "calm",
+            WindBand::Breeze => "breeze",
+            WindBand::Gusty => "gusty",
+            WindBand::Storm => "storm",
         }
+    }
 
-        let mut reader = hound::WavReader::open(wind_path)
-            .map_err(|e| format!("open {}: {e}", wind_path.display()))?;
-        
-        let wind_spec = reader.spec();
-        let wind_data: Vec<f32> = match wind_spec.sample_format {
-            hound::SampleFormat::Float => {
-                reader.samples::<f32>().map(|s| s.unwrap_or(0.0)).collect()
+    /// Position in the mapped input range where this band plays alone.
+    fn center(self) -> f32 {
+        match self {
+            WindBand::Calm => 0.0,
+            WindBand::Breeze => 1.0 / 3.0,
+            WindBand::Gusty => 2.0 / 3.0,
+            WindBand::Storm => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindSource {
+    /// The single `assets/Wind_Loop.wav`, only scaled in volume
+    Loop,
+    /// Band-tagged recordings in `assets/wind/` crossfaded by intensity
+    Layers,
+    /// Filtered noise synthesized in-process, needs no recordings
+    Procedural,
+}
+
+/// Tone stage on recorded wind: the lowpass opens and the treble lifts as the
+/// wind picks up, so calm readings sound distant and strong ones close.
+#[derive(Clone, Debug)]
+pub struct WindFilter {
+    pub enabled: bool,
+    /// Lowpass cutoff in Hz at the bottom and top of the input range
+    pub cutoff_min: f32,
+    pub cutoff_max: f32,
+    /// Lowpass Q reached at the top of the range, from a flat 0.707 at the bottom
+    pub resonance: f32,
+    /// High shelf gain in dB at the top of the range, cutting by as much at the bottom
+    pub shelf_db: f32,
+}
+
+impl Default for WindFilter {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cutoff_min: 500.0,
+            cutoff_max: 16000.0,
+            resonance: 1.2,
+            shelf_db: 4.0,
+        }
+    }
+}
+
+/// Speakers the wind is panned across when the readings carry a direction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpeakerLayout {
+    Stereo,
+    Quad,
+    Surround51,
+    Octagon,
+}
+
+impl SpeakerLayout {
+    /// Azimuth of each output channel in degrees clockwise from the front, in
+    /// ffmpeg's channel order. `None` marks the LFE, which gets no wind.
+    fn azimuths(self) -> &'static [Option<f32>] {
+        match self {
+            SpeakerLayout::Stereo => &[Some(-90.0), Some(90.0)],
+            SpeakerLayout::Quad => &[Some(-45.0), Some(45.0), Some(-135.0), Some(135.0)],
+            SpeakerLayout::Surround51 => &[Some(-30.0), Some(30.0), Some(0.0), None, Some(-110.0), Some(110.0)],
+            SpeakerLayout::Octagon => &[
+                Some(-45.0), Some(45.0), Some(0.0), Some(-135.0),
+                Some(135.0), Some(180.0), Some(-90.0), Some(90.0),
+            ],
+        }
+    }
+
+    pub fn channels(self) -> usize {
+        self.azimuths().len()
+    }
+
+    fn ffmpeg_layout(self) -> &'static str {
+        match self {
+            SpeakerLayout::Stereo => "stereo",
+            SpeakerLayout::Quad => "quad",
+            SpeakerLayout::Surround51 => "5.1",
+            SpeakerLayout::Octagon => "octagon",
+        }
+    }
+
+    /// Constant-power gains placing a source at `direction` degrees. Stereo
+    /// projects the direction onto the left-right axis, the surround layouts
+    /// pan between the two speakers either side of it.
+    fn pan_gains(self, direction: f32, gains: &mut [f32]) {
+        gains.iter_mut().for_each(|g| *g = 0.0);
+        if self == SpeakerLayout::Stereo {
+            let pan = direction.to_radians().sin();
+            let angle = (pan + 1.0) * std::f32::consts::FRAC_PI_4;
+            gains[0] = angle.cos();
+            gains[1] = angle.sin();
+            return;
+        }
+
+        let mut speakers: Vec<(usize, f32)> = self
+            .azimuths()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, a)| a.map(|a| (i, a.rem_euclid(360.0))))
+            .collect();
+        speakers.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let direction = direction.rem_euclid(360.0);
+        let next = speakers.iter().position(|s| s.1 >= direction).unwrap_or(0);
+        let prev = (next + speakers.len() - 1) % speakers.len();
+        let (a, from) = speakers[prev];
+        let (b, to) = speakers[next];
+        let span = (to - from).rem_euclid(360.0);
+        let u = if span > 0.0 { (direction - from).rem_euclid(360.0) / span } else { 0.0 };
+        let angle = u * std::f32::consts::FRAC_PI_2;
+        gains[a] += angle.cos();
+        gains[b] += angle.sin();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WindOptions {
+    pub mapping: WindMapping,
+    pub source: WindSource,
+    pub looping: LoopSettings,
+    pub filter: WindFilter,
+    /// Readings are `intensity direction` pairs, e.g. `4 NE` or `4 045`
+    pub directions: bool,
+    pub layout: SpeakerLayout,
+    pub interpolation: Interpolation,
+    pub gusts: GustSettings,
+    /// Also writes intensity (CC 1) and direction (CC 16) curves as a Standard
+    /// MIDI File, `{name}.mid`
+    pub midi: bool,
+}
+
+impl Default for WindOptions {
+    fn default() -> Self {
+        Self {
+            mapping: WindMapping::default(),
+            source: WindSource::Loop,
+            looping: LoopSettings::default(),
+            filter: WindFilter::default(),
+            directions: false,
+            layout: SpeakerLayout::Stereo,
+            interpolation: Interpolation::default(),
+            gusts: GustSettings::default(),
+            midi: false,
+        }
+    }
+}
+
+struct Layer {
+    center: f32,
+    player: LoopPlayer,
+}
+
+// Control values glide slowly, so the filters are only retuned every block
+const FILTER_BLOCK: usize = 16;
+
+/// Stereo wind from pink noise through a resonant lowpass. Intensity opens the
+/// cutoff and raises the resonance into a whistle, while a slow random gust
+/// signal sweeps both and swells the level more the stronger the wind.
+struct ProceduralWind {
+    sample_rate: u32,
+    noise: [PinkNoise; 2],
+    filters: [Svf; 2],
+    rng: Rng,
+    position: Smoother,
+    gust: Smoother,
+    gust_target: f32,
+    gust_countdown: usize,
+    drive: f32,
+    q: f32,
+    block: usize,
+}
+
+impl ProceduralWind {
+    fn new(seed: u64, sample_rate: u32) -> Self {
+        let mut rng = Rng::new(seed);
+        let noise = [PinkNoise::new(rng.next_u64()), PinkNoise::new(rng.next_u64())];
+        Self {
+            sample_rate,
+            noise,
+            filters: [Svf::new(150.0, 0.6, sample_rate), Svf::new(150.0, 0.6, sample_rate)],
+            rng,
+            position: Smoother::new(0.05, sample_rate, 0.0),
+            gust: Smoother::new(0.8, sample_rate, 0.5),
+            gust_target: 0.5,
+            gust_countdown: 0,
+            drive: 0.0,
+            q: 0.6,
+            block: 0,
+        }
+    }
+
+    fn next_frame(&mut self, position: f32, out: &mut [f32]) {
+        // New gust level every 0.5-3 s, glided so it rises and falls like a gust
+        if self.gust_countdown == 0 {
+            self.gust_target = self.rng.next_f32();
+            self.gust_countdown = ((0.5 + 2.5 * self.rng.next_f32()) * self.sample_rate as f32) as usize;
+        }
+        self.gust_countdown -= 1;
+        let gust = self.gust.next(self.gust_target) - 0.5;
+        let position = self.position.next(position);
+
+        if self.block == 0 {
+            self.drive = (position + 0.6 * position * gust).clamp(0.0, 1.0);
+            let cutoff = 150.0 * 30f32.powf(self.drive);
+            self.q = 0.6 + 5.0 * self.drive * self.drive;
+            for f in self.filters.iter_mut() {
+                f.set(cutoff, self.q, self.sample_rate);
             }
-            hound::SampleFormat::Int => {
-                let max_val = match wind_spec.bits_per_sample {
-                    8 => 128.0,
-                    16 => 32768.0,
-                    24 => 8388608.0,
-                    32 => 2147483648.0,
-                    _ => 32768.0,
-                };
-                reader.samples::<i32>().map(|s| (s.unwrap_or(0) as f32) / max_val).collect()
+        }
+        self.block = (self.block + 1) % FILTER_BLOCK;
+
+        // Sharing part of the noise keeps the image wide but not disjointed
+        let left = self.noise[0].next();
+        let right = self.noise[1].next();
+        let inputs = [left + 0.3 * right, right + 0.3 * left];
+        let level = (1.0 + position * gust) / self.q.sqrt();
+        let mut frame = [0.0f32; 2];
+        for ((s, f), x) in frame.iter_mut().zip(self.filters.iter_mut()).zip(inputs) {
+            let out = f.process(x);
+            *s = (out.low + 0.5 * self.drive * out.band) * level;
+        }
+
+        if out.len() == 1 {
+            out[0] = 0.5 * (frame[0] + frame[1]);
+        } else {
+            out.iter_mut().zip(frame.iter().cycle()).for_each(|(o, s)| *o = *s);
+        }
+    }
+}
+
+const SHELF_FREQ: f32 = 3000.0;
+
+/// Runs `WindFilter` over the bed, one lowpass and shelf per channel.
+struct ToneStage {
+    settings: WindFilter,
+    sample_rate: u32,
+    lowpass: Vec<Svf>,
+    shelf: Vec<HighShelf>,
+    position: Smoother,
+    block: usize,
+}
+
+impl ToneStage {
+    fn new(settings: WindFilter, channels: usize, sample_rate: u32) -> Self {
+        Self {
+            lowpass: vec![Svf::new(settings.cutoff_min, 0.707, sample_rate); channels],
+            shelf: vec![HighShelf::new(SHELF_FREQ, -settings.shelf_db, sample_rate); channels],
+            settings,
+            sample_rate,
+            position: Smoother::new(0.05, sample_rate, 0.0),
+            block: 0,
+        }
+    }
+
+    fn process(&mut self, position: f32, frame: &mut [f32]) {
+        let position = self.position.next(position);
+        if self.block == 0 {
+            let s = &self.settings;
+            let ratio = (s.cutoff_max / s.cutoff_min.max(1.0)).max(1.0);
+            let cutoff = s.cutoff_min * ratio.powf(position);
+            let q = 0.707 + (s.resonance - 0.707) * position;
+            let shelf_db = s.shelf_db * (2.0 * position - 1.0);
+            for (lp, shelf) in self.lowpass.iter_mut().zip(self.shelf.iter_mut()) {
+                lp.set(cutoff, q, self.sample_rate);
+                shelf.set(SHELF_FREQ, shelf_db, self.sample_rate);
             }
-        };
-        
-        let n_wind = wind_data.len();
-        let sample_rate = 44100u32;
-        let total_duration_secs = 12.0 * 60.0; // 12 minutes
-        let target_samples = (sample_rate as f64 * total_duration_secs) as usize;
-        
-        let n_points = wind_intensities.len();
+        }
+        self.block = (self.block + 1) % FILTER_BLOCK;
+
+        for ((s, lp), shelf) in frame.iter_mut().zip(self.lowpass.iter_mut()).zip(self.shelf.iter_mut()) {
+            *s = shelf.process(lp.process(*s).low);
+        }
+    }
+}
+
+/// Whatever produces the wind before the intensity gain is applied.
+enum WindBed {
+    Recorded(Vec<Layer>),
+    Procedural(ProceduralWind),
+}
+
+impl WindBed {
+    fn channels(&self) -> usize {
+        match self {
+            // Stereo as soon as any recording is, mono recordings then sit in the centre
+            WindBed::Recorded(layers) => layers.iter().map(|l| l.player.channels()).max().unwrap_or(1),
+            WindBed::Procedural(_) => 2,
+        }
+    }
+
+    fn next_frame(&mut self, position: f32, out: &mut [f32]) {
+        match self {
+            WindBed::Recorded(layers) => bed_frame(layers, position, out),
+            WindBed::Procedural(wind) => wind.next_frame(position, out),
+        }
+    }
+}
+
+const WIND_LOOP: &str = "assets/Wind_Loop.wav";
+const WIND_LAYER_DIR: &str = "assets/wind";
+
+/// Loads the recordings of the selected source sorted by band, or sets up the
+/// synthesizer when no recordings are wanted.
+fn load_bed(source: WindSource, looping: &LoopSettings, sample_rate: u32) -> Result<WindBed, String> {
+    let layer = |center: f32, path: &Path, index: u64| -> Result<Layer, String> {
+        let clip = audio::load_clip(path, sample_rate)?;
+        Ok(Layer { center, player: LoopPlayer::new(&clip, looping, index) })
+    };
+
+    match source {
+        WindSource::Loop => {
+            let wind_path = Path::new(WIND_LOOP);
+            if !wind_path.exists() {
+                return Err(format!("{WIND_LOOP} not found (the procedural source needs no recording)"));
+            }
+            Ok(WindBed::Recorded(vec![layer(0.0, wind_path, 0)?]))
+        }
+        WindSource::Layers => {
+            let dir = Path::new(WIND_LAYER_DIR);
+            let entries = fs::read_dir(dir).map_err(|e| format!("read_dir {}: {e}", dir.display()))?;
+            let mut files: Vec<_> = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().and_then(|e| e.to_str()).map(|e| e.eq_ignore_ascii_case("wav")) == Some(true))
+                .collect();
+            files.sort();
+
+            // A file belongs to the first band its name starts with, e.g. "storm_2019.wav"
+            let mut layers = Vec::new();
+            for (index, band) in WindBand::ALL.into_iter().enumerate() {
+                let file = files.iter().find(|p| {
+                    p.file_stem()
+                        .map(|s| s.to_string_lossy().to_lowercase().starts_with(band.tag()))
+                        .unwrap_or(false)
+                });
+                if let Some(file) = file {
+                    layers.push(layer(band.center(), file, index as u64)?);
+                }
+            }
+            if layers.is_empty() {
+                return Err(format!(
+                    "No wind layers found in {WIND_LAYER_DIR} (expected calm/breeze/gusty/storm WAV files)"
+                ));
+            }
+            Ok(WindBed::Recorded(layers))
+        }
+        WindSource::Procedural => Ok(WindBed::Procedural(ProceduralWind::new(looping.seed, sample_rate))),
+    }
+}
+
+/// Mixes the layers for a mapped intensity position into `out` with an
+/// equal-power crossfade between the two bands on either side of it. Every layer
+/// keeps playing while silent so a band fades back in mid-recording.
+fn bed_frame(layers: &mut [Layer], position: f32, out: &mut [f32]) {
+    let last = layers.len() - 1;
+    let (a, b, u) = if position <= layers[0].center {
+        (0, 0, 0.0)
+    } else if position >= layers[last].center {
+        (last, last, 0.0)
+    } else {
+        let k = layers.windows(2).position(|w| position <= w[1].center).unwrap_or(last - 1);
+        let u = (position - layers[k].center) / (layers[k + 1].center - layers[k].center);
+        (k, k + 1, u)
+    };
+
+    let angle = u * std::f32::consts::FRAC_PI_2;
+    let mut frame = [0.0f32; 2];
+    let frame = &mut frame[..out.len()];
+    out.iter_mut().for_each(|s| *s = 0.0);
+    for (k, layer) in layers.iter_mut().enumerate() {
+        layer.player.next_frame(frame);
+        let gain = if k == a { angle.cos() } else { 0.0 } + if k == b { angle.sin() } else { 0.0 };
+        for (o, s) in out.iter_mut().zip(frame.iter()) {
+            *o += s * gain;
+        }
+    }
+}
+
+/// Fills readings without a direction from the nearest earlier one, or the
+/// first known one at the start. Without any direction the wind stays in front.
+fn fill_directions(directions: &[Option<f32>]) -> Vec<f32> {
+    let first = directions.iter().flatten().next().copied().unwrap_or(0.0);
+    let mut last = first;
+    directions
+        .iter()
+        .map(|d| {
+            if let Some(d) = d {
+                last = *d;
+            }
+            last
+        })
+        .collect()
+}
+
+/// Interpolates between two bearings the short way round.
+fn lerp_direction(from: f32, to: f32, t: f32) -> f32 {
+    let delta = (to - from + 540.0).rem_euclid(360.0) - 180.0;
+    (from + delta * t).rem_euclid(360.0)
+}
+
+struct Gust {
+    frame: usize,
+    strength: f32,
+    direction: f32,
+}
+
+/// RMS of the interleaved frames `from..to`, clamped to the buffer.
+fn rms(samples: &[f32], channels: usize, from: usize, to: usize) -> f32 {
+    let frames = samples.len() / channels;
+    let (from, to) = (from.min(frames), to.min(frames));
+    if to <= from {
+        return 0.0;
+    }
+    let slice = &samples[from * channels..to * channels];
+    (slice.iter().map(|s| (*s as f64).powi(2)).sum::<f64>() / slice.len() as f64).sqrt() as f32
+}
+
+/// Mixes an accent into the output for every gust, `level` times as loud as the
+/// wind in the second around it and placed with the wind when it is panned.
+fn mix_gusts(
+    output: &mut [f32],
+    channels: usize,
+    sample_rate: u32,
+    gusts: &[Gust],
+    accents: &mut Accents,
+    level: f32,
+    layout: Option<SpeakerLayout>,
+) {
+    let frames = output.len() / channels;
+    let around = sample_rate as usize;
+    let overall = rms(output, channels, 0, frames);
+    let mut pan = vec![0.0f32; channels];
+
+    for gust in gusts {
+        let (clip, peak) = accents.next(gust.strength);
+        let samples = (clip.frames() * clip.channels.len()).max(1);
+        let clip_rms = (clip.channels.iter().flatten().map(|s| s * s).sum::<f32>() / samples as f32).sqrt();
+        if clip_rms <= 0.0 {
+            continue;
+        }
+        // Near-silent stretches would leave the gust inaudible, so the whole
+        // file's level is the floor
+        let local = rms(output, channels, gust.frame.saturating_sub(around), gust.frame + around);
+        let gain = local.max(overall * 0.5) * level / clip_rms;
+        let start = gust.frame.saturating_sub(peak);
+        if let Some(layout) = layout {
+            layout.pan_gains(gust.direction, &mut pan);
+        }
+
+        let mono = clip.mono();
+        for (i, frame) in output.chunks_mut(channels).skip(start).take(clip.frames()).enumerate() {
+            match layout {
+                Some(_) => frame.iter_mut().zip(&pan).for_each(|(o, p)| *o += mono[i] * p * gain),
+                None if channels == 1 => frame[0] += mono[i] * gain,
+                None => {
+                    for (c, o) in frame.iter_mut().enumerate() {
+                        *o += clip.channels[c % clip.channels.len()][i] * gain;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A synthesized wind file, ready to encode or mix.
+pub struct WindRender {
+    /// Interleaved, `channels` samples per frame
+    pub samples: Vec<f32>,
+    pub channels: usize,
+    pub sample_rate: u32,
+    pub duration_secs: f64,
+    intensities: Vec<f32>,
+    directions: Vec<f32>,
+    /// Held for as long as the samples are
+    _memory: MemoryLease,
+}
+
+/// Where a wind PDF sits in real time: whole days from midnight of day 1, placed
+/// by the first day label when it is a day of the month.
+fn real_span(dataset: &WindDataset) -> (f64, DataSpan) {
+    let first_day = dataset.days.first().and_then(|d| d.label.parse::<u32>().ok()).unwrap_or(1);
+    let days = dataset.days.len();
+    (
+        first_day.saturating_sub(1) as f64 * 86400.0,
+        DataSpan { readings: dataset.readings().count(), days, real_secs: days as f64 * 86400.0 },
+    )
+}
+
+/// Start and extent of a wind PDF's records, for lining tracks up in a mix.
+pub fn data_span(pdf: &Path, options: &WindOptions) -> Result<(f64, DataSpan), String> {
+    let text = shared::extract_text(pdf)?;
+    let m = &options.mapping;
+    Ok(real_span(&WindDataset::parse(&text, options.directions, m.input_min, m.input_max)))
+}
+
+/// Parses one wind PDF, writes its `_report.txt` and synthesizes the audio at
+/// the loudness target.
+pub fn render_wind(
+    pdf: &Path,
+    options: &WindOptions,
+    timeline: Timeline,
+    out_dir: &Path,
+    name: &str,
+    prog_tx: &ProgressTx,
+    cancel: CancelFlag,
+) -> Result<WindRender, String> {
+    let layout = options.directions.then_some(options.layout);
+    let text = shared::extract_text(pdf)?;
+    
+    let dataset = WindDataset::parse(&text, options.directions, options.mapping.input_min, options.mapping.input_max);
+    let report = out_dir.join(format!("{name}_report.txt"));
+    fs::write(&report, dataset.report(name)).map_err(|e| format!("write {}: {e}", report.display()))?;
+    if !dataset.anomalies.is_empty() {
+        let _ = prog_tx.send(super::Progress::Warning {
+            name: name.to_string(),
+            message: format!("{} anomalies in the wind data, see the _report.txt", dataset.anomalies.len()),
+        });
+    }
+
+    // Days play back to back as one continuous series
+    let wind_intensities = dataset.intensities();
+    let wind_directions = dataset.directions();
+    if wind_intensities.is_empty() {
+        return Err("No wind intensity data found".into());
+    }
+
+    let sample_rate = 44100u32;
+    // Files in one folder would otherwise share every noise and gust texture
+    let looping = LoopSettings { seed: options.looping.seed.wrapping_add(shared::name_seed(name)), ..options.looping.clone() };
+    let mut bed = load_bed(options.source, &looping, sample_rate)?;
+    let bed_channels = bed.channels();
+    // The synthesizer already shapes its spectrum by intensity
+    let mut tone = (options.filter.enabled && !matches!(bed, WindBed::Procedural(_)))
+        .then(|| ToneStage::new(options.filter.clone(), bed_channels, sample_rate));
+    let directions = fill_directions(&wind_directions);
+    let channels = layout.map_or(bed_channels, |l| l.channels());
+    let total_duration_secs = timeline.checked_duration_secs(real_span(&dataset).1, prog_tx, name);
+    let target_samples = (sample_rate as f64 * total_duration_secs) as usize;
+    
+    let n_points = wind_intensities.len();
+    let series = Series::new(&wind_intensities, options.interpolation);
+
+    let memory = MemoryLease::reserve(target_samples * channels, &cancel)?;
+    let mut output: Vec<f32> = Vec::with_capacity(target_samples * channels);
+    let mut frame = vec![0.0f32; bed_channels];
+    let mut pan = vec![0.0f32; channels];
+
+    for i in 0..target_samples {
+        if i % 44100 == 0 && cancel.load(std::sync::atomic::Ordering::Relaxed) {
+            return Err("Cancelled.".into());
+        }
+
+        // Map current sample directly to fractional position across all data points
+        let progress = i as f64 / target_samples as f64;
+        let exact_index = progress * (n_points.saturating_sub(1) as f64);
         
-        let mut output: Vec<f32> = Vec::with_capacity(target_samples);
+        let intensity = series.at_index(exact_index);
 
-        for i in 0..target_samples {
-            if i % 44100 == 0 && cancel.load(std::sync::atomic::Ordering::Relaxed) {
-                return Err("Cancelled.".into());
+        let position = options.mapping.position(intensity);
+        bed.next_frame(position, &mut frame);
+        if let Some(tone) = tone.as_mut() {
+            tone.process(position, &mut frame);
+        }
+        let gain = options.mapping.gain(intensity);
+        match layout {
+            Some(layout) => {
+                // The bed is folded to mono so the direction alone places it
+                let mono = frame.iter().sum::<f32>() / bed_channels as f32;
+                // Bearings wrap around, so they keep their own interpolation
+                let idx1 = exact_index.floor() as usize;
+                let idx2 = (idx1 + 1).min(n_points - 1);
+                let frac = (exact_index - idx1 as f64) as f32;
+                let direction = lerp_direction(directions[idx1], directions[idx2], frac);
+                layout.pan_gains(direction, &mut pan);
+                output.extend(pan.iter().map(|p| mono * p * gain));
             }
+            None => output.extend(frame.iter().map(|s| s * gain)),
+        }
+    }
 
-            // Map current sample directly to fractional position across all data points
-            let progress = i as f64 / target_samples as f64;
-            let exact_index = progress * (n_points.saturating_sub(1) as f64);
-            
-            let idx1 = exact_index.floor() as usize;
-            let idx2 = (idx1 + 1).min(n_points - 1);
-            let frac = (exact_index - idx1 as f64) as f32;
+    if options.gusts.enabled {
+        let mut accents = Accents::new(options.gusts.sound, looping.seed, sample_rate)?;
+        let last = n_points.saturating_sub(1).max(1) as f64;
+        let gusts: Vec<Gust> = gust::detect(&wind_intensities, &options.gusts)
+            .into_iter()
+            .map(|event| Gust {
+                frame: (event.index as f64 / last * target_samples as f64) as usize,
+                strength: event.strength,
+                direction: directions[event.index],
+            })
+            .collect();
+        mix_gusts(&mut output, channels, sample_rate, &gusts, &mut accents, options.gusts.level, layout);
+    }
 
-            // Perfectly smooth linear interpolation across the entire timeline
-            let intensity = wind_intensities[idx1] + (wind_intensities[idx2] - wind_intensities[idx1]) * frac;
+    loudness::normalize(&mut output, channels, sample_rate);
+    let ceiling = options.mapping.ceiling.clamp(0.0, 1.0);
+    output.iter_mut().for_each(|s| *s *= ceiling);
 
-            let wind_index = i % n_wind;
-            
-            let value = if intensity <= 1.0 {
-                0.0
-            } else {
-                wind_data[wind_index] * intensity / 15.0
-            };
+    Ok(WindRender {
+        samples: output,
+        channels,
+        sample_rate,
+        duration_secs: total_duration_secs,
+        intensities: wind_intensities,
+        directions,
+        _memory: memory,
+    })
+}
 
-            // Audio gain matching python with a hard clip protector
-            output.push((value * 1.412).clamp(-1.0, 1.0));
+pub fn convert_wind(
+    file_path: &Path,
+    is_folder: bool,
+    options: WindOptions,
+    timeline: Timeline,
+    tx: ProgressTx,
+    cancel: CancelFlag,
+) -> Result<(), String> {
+    shared::process_files(file_path, is_folder, tx, cancel.clone(), |pdf, out_dir, name, prog_tx| {
+        // MP3 stops at stereo, so surround layouts are written losslessly instead
+        let layout = options.directions.then_some(options.layout);
+        let ext = if layout.is_some_and(|l| l.channels() > 2) { "flac" } else { "mp3" };
+        let out = out_dir.join(format!("{name}.{ext}"));
+        let partial_out = out.with_extension(format!("tmp.{ext}"));
+        if out.exists() {
+            return Ok(());
         }
 
+        let render = render_wind(pdf, &options, timeline, out_dir, name, prog_tx, cancel.clone())?;
+
         let tmp_dir = shared::make_temp_dir("wind")?;
         let tmp = tmp_dir.join(format!("{name}_tmp.wav"));
-        {
-            let spec = hound::WavSpec {
-                channels: 1,
-                sample_rate,
-                bits_per_sample: 32,
-                sample_format: hound::SampleFormat::Float,
-            };
-            let mut w = hound::WavWriter::create(&tmp, spec)
-                .map_err(|e| format!("create {}: {e}", tmp.display()))?;
-            for s in &output {
-                w.write_sample(*s).map_err(|e| e.to_string())?;
-            }
-            w.finalize().map_err(|e| e.to_string())?;
-        }
+        audio::write_wav(&tmp, &render.samples, render.channels, render.sample_rate)?;
 
         let _ = prog_tx.send(super::Progress::Update { name: name.to_string(), fraction: 0.5 });
 
-        let args: Vec<String> = vec![
+        let mut args: Vec<String> = vec![
             "-y".into(), "-hide_banner".into(), "-loglevel".into(), "error".into(), "-stats".into(),
             "-i".into(), tmp.to_string_lossy().to_string(),
-            "-vn".into(), "-ar".into(), "44100".into(), "-ac".into(), "2".into(),
-            "-b:a".into(), "192k".into(), "-codec:a".into(), "libmp3lame".into(),
-            partial_out.to_string_lossy().to_string(),
+            "-vn".into(), "-ar".into(), "44100".into(),
         ];
+        match layout.filter(|l| l.channels() > 2) {
+            // The WAV header cannot say which speaker each channel belongs to
+            Some(layout) => args.extend([
+                "-af".into(), format!("channelmap=channel_layout={}", layout.ffmpeg_layout()),
+                "-codec:a".into(), "flac".into(),
+            ]),
+            None => args.extend([
+                "-ac".into(), "2".into(),
+                "-b:a".into(), "192k".into(), "-codec:a".into(), "libmp3lame".into(),
+            ]),
+        }
+        args.push(partial_out.to_string_lossy().to_string());
         
         let result = shared::run_ffmpeg(&args, None, prog_tx, name, cancel.clone());
 
@@ -130,6 +788,30 @@ pub fn convert_wind(
         
         if result.is_ok() && !cancel.load(std::sync::atomic::Ordering::Relaxed) {
             let _ = fs::rename(&partial_out, &out);
+            let graph = out_dir.join(format!("{name}_waveform.png"));
+            if let Err(e) = preview::write_waveform(&graph, &render.samples, render.channels, &render.intensities) {
+                let _ = prog_tx.send(super::Progress::Warning { name: name.to_string(), message: format!("waveform image: {e}") });
+            }
+            if options.midi {
+                let series = Series::new(&render.intensities, options.interpolation);
+                let directions = &render.directions;
+                let n_points = render.intensities.len();
+                let last = n_points.saturating_sub(1) as f64;
+                let mut controllers = vec![midi::Controller::new(1, "Wind intensity", render.duration_secs, |p| {
+                    options.mapping.position(series.at(p))
+                })];
+                if options.directions {
+                    controllers.push(midi::Controller::new(16, "Wind direction", render.duration_secs, |p| {
+                        let index = p * last;
+                        let i = index.floor() as usize;
+                        let j = (i + 1).min(n_points - 1);
+                        lerp_direction(directions[i], directions[j], (index - i as f64) as f32) / 360.0
+                    }));
+                }
+                if let Err(e) = midi::write_curves(&out_dir.join(format!("{name}.mid")), &controllers) {
+                    let _ = prog_tx.send(super::Progress::Warning { name: name.to_string(), message: format!("MIDI export: {e}") });
+                }
+            }
         } else {
             let _ = fs::remove_file(&partial_out);
         }
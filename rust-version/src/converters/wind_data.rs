@@ -0,0 +1,258 @@
+use std::fmt;
+
+const COMPASS: [&str; 16] = [
+    "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE",
+    "S", "SSW", "SW", "WSW", "W", "WNW", "NW", "NNW",
+];
+
+/// Placeholders the records use for a reading that was never taken.
+const MISSING_MARKS: [&str; 5] = ["-", "--", "?", "x", "n/a"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindReading {
+    pub intensity: f32,
+    /// Degrees clockwise from north, only when the records carry a direction
+    pub direction: Option<f32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct WindDay {
+    /// Label as printed at the start of the line, e.g. `01`
+    pub label: String,
+    /// First line of the extracted text the day appeared on, 1-based
+    pub line: usize,
+    pub readings: Vec<WindReading>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnomalyKind {
+    /// Empty slot between commas or a placeholder like `-`
+    MissingReading,
+    /// Entry that is neither a number nor a known placeholder
+    Illegible(String),
+    /// Kept as read, the mapping clamps it
+    OutOfRange(f32),
+    /// Kept, the direction carries over from the previous reading
+    MissingDirection,
+    /// Readings before the first day label, kept in an unlabelled day
+    NoDayLabel,
+    EmptyDay,
+    /// Day numbers missing between two labels
+    SkippedDays(u32, u32),
+    DayOutOfOrder,
+    /// Line without any reading, e.g. a heading, skipped
+    Unrecognized(String),
+}
+
+impl fmt::Display for AnomalyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnomalyKind::MissingReading => write!(f, "missing reading"),
+            AnomalyKind::Illegible(entry) => write!(f, "illegible entry '{entry}' skipped"),
+            AnomalyKind::OutOfRange(v) => write!(f, "reading {v} outside the input range"),
+            AnomalyKind::MissingDirection => write!(f, "reading without a direction"),
+            AnomalyKind::NoDayLabel => write!(f, "readings before the first day label"),
+            AnomalyKind::EmptyDay => write!(f, "day has no readings"),
+            AnomalyKind::SkippedDays(from, to) if from == to => write!(f, "day {from} missing"),
+            AnomalyKind::SkippedDays(from, to) => write!(f, "days {from}-{to} missing"),
+            AnomalyKind::DayOutOfOrder => write!(f, "day label out of order"),
+            AnomalyKind::Unrecognized(text) => write!(f, "unrecognized line '{text}' skipped"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Anomaly {
+    pub line: usize,
+    pub day: Option<String>,
+    pub kind: AnomalyKind,
+}
+
+/// Wind records as printed: one line per day, a day label followed by the
+/// comma-separated readings of that day, which may wrap onto further lines.
+#[derive(Clone, Debug, Default)]
+pub struct WindDataset {
+    pub days: Vec<WindDay>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+/// Direction in degrees from a compass point or a bearing like `270` or `270°`.
+fn parse_direction(token: &str) -> Option<f32> {
+    let token = token.trim().trim_end_matches('°');
+    if let Some(i) = COMPASS.iter().position(|c| c.eq_ignore_ascii_case(token)) {
+        return Some(i as f32 * 22.5);
+    }
+    token.parse::<f32>().ok().filter(|d| d.is_finite()).map(|d| d.rem_euclid(360.0))
+}
+
+/// Parses one comma-separated entry, `4` or with directions `4 NE`, `4/045`, `4@045`.
+fn parse_entry(entry: &str, directions: bool) -> Result<WindReading, AnomalyKind> {
+    let entry = entry.trim();
+    if entry.is_empty() || MISSING_MARKS.iter().any(|m| m.eq_ignore_ascii_case(entry)) {
+        return Err(AnomalyKind::MissingReading);
+    }
+    let illegible = || AnomalyKind::Illegible(entry.to_string());
+
+    if !directions {
+        let intensity = entry.parse::<f32>().ok().filter(|v| v.is_finite()).ok_or_else(illegible)?;
+        return Ok(WindReading { intensity, direction: None });
+    }
+
+    let mut parts = entry.split(|c: char| c.is_whitespace() || c == '/' || c == '@').filter(|p| !p.is_empty());
+    let intensity = parts.next().and_then(|p| p.parse::<f32>().ok()).filter(|v| v.is_finite()).ok_or_else(illegible)?;
+    let direction = match parts.next() {
+        Some(token) => Some(parse_direction(token).ok_or_else(illegible)?),
+        None => None,
+    };
+    if parts.next().is_some() {
+        return Err(illegible());
+    }
+    Ok(WindReading { intensity, direction })
+}
+
+/// Splits a leading day label such as `01`, `1.` or `1:` off a line.
+fn split_label(line: &str) -> Option<(&str, &str)> {
+    let (first, rest) = line.split_once(char::is_whitespace)?;
+    let label = first.trim_end_matches(['.', ':']);
+    let is_label = !label.is_empty() && label.len() <= 3 && label.chars().all(|c| c.is_ascii_digit());
+    (is_label && !rest.trim().is_empty()).then_some((label, rest))
+}
+
+/// Whether a line that did not parse was meant as data: most of its tokens hold
+/// digits, unlike headings such as `Wind log 2023` or `Page 3 of 10`.
+fn looks_like_data(line: &str) -> bool {
+    let tokens: Vec<&str> = line.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty()).collect();
+    let numeric = tokens.iter().filter(|t| t.chars().any(|c| c.is_ascii_digit())).count();
+    numeric * 2 > tokens.len()
+}
+
+impl WindDataset {
+    /// Parses extracted PDF text. Readings outside `input_min..=input_max` are kept
+    /// but reported, everything that cannot be read is reported and left out.
+    pub fn parse(text: &str, directions: bool, input_min: f32, input_max: f32) -> Self {
+        let mut data = WindDataset::default();
+        let mut last_day: Option<u32> = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let line = line.trim();
+            if !line.chars().any(|c| c.is_ascii_digit()) {
+                continue;
+            }
+
+            // A wrapped line starts with a reading, which can look like a label
+            // when directions are on (`4 NE, ...`), so a label only counts if
+            // what follows it reads as the start of a list.
+            let first_ok = |s: &str| parse_entry(s.split(',').next().unwrap_or(""), directions).is_ok();
+            let (label, rest) = match split_label(line) {
+                Some((label, rest)) if first_ok(rest) || !first_ok(line) => (Some(label), rest),
+                _ => (None, line),
+            };
+
+            let mut entries: Vec<&str> = rest.split(',').collect();
+            // A trailing comma just means the list wraps onto the next line
+            if entries.len() > 1 && entries.last().is_some_and(|e| e.trim().is_empty()) {
+                entries.pop();
+            }
+            let parsed: Vec<Result<WindReading, AnomalyKind>> =
+                entries.iter().map(|e| parse_entry(e, directions)).collect();
+
+            match label {
+                Some(label) => {
+                    if let Ok(number) = label.parse::<u32>() {
+                        match last_day {
+                            Some(prev) if number <= prev => data.anomalies.push(Anomaly {
+                                line: line_no,
+                                day: Some(label.to_string()),
+                                kind: AnomalyKind::DayOutOfOrder,
+                            }),
+                            Some(prev) if number > prev + 1 => data.anomalies.push(Anomaly {
+                                line: line_no,
+                                day: Some(label.to_string()),
+                                kind: AnomalyKind::SkippedDays(prev + 1, number - 1),
+                            }),
+                            _ => {}
+                        }
+                        last_day = Some(number);
+                    }
+                    data.days.push(WindDay { label: label.to_string(), line: line_no, readings: Vec::new() });
+                }
+                None if !parsed.iter().any(|r| r.is_ok()) => {
+                    if looks_like_data(line) {
+                        data.anomalies.push(Anomaly { line: line_no, day: None, kind: AnomalyKind::Unrecognized(line.to_string()) });
+                    }
+                    continue;
+                }
+                None if data.days.is_empty() => {
+                    data.anomalies.push(Anomaly { line: line_no, day: None, kind: AnomalyKind::NoDayLabel });
+                    data.days.push(WindDay { label: String::new(), line: line_no, readings: Vec::new() });
+                }
+                None => {}
+            }
+
+            let day = data.days.last_mut().expect("a day was pushed above");
+            let day_label = (!day.label.is_empty()).then(|| day.label.clone());
+            for result in parsed {
+                let kind = match result {
+                    Ok(reading) => {
+                        let out_of_range = reading.intensity < input_min || reading.intensity > input_max;
+                        let no_direction = directions && reading.direction.is_none();
+                        day.readings.push(reading);
+                        if out_of_range {
+                            AnomalyKind::OutOfRange(reading.intensity)
+                        } else if no_direction {
+                            AnomalyKind::MissingDirection
+                        } else {
+                            continue;
+                        }
+                    }
+                    Err(kind) => kind,
+                };
+                data.anomalies.push(Anomaly { line: line_no, day: day_label.clone(), kind });
+            }
+        }
+
+        for day in data.days.iter().filter(|d| d.readings.is_empty()) {
+            data.anomalies.push(Anomaly { line: day.line, day: Some(day.label.clone()), kind: AnomalyKind::EmptyDay });
+        }
+        data.anomalies.sort_by_key(|a| a.line);
+        data
+    }
+
+    pub fn readings(&self) -> impl Iterator<Item = &WindReading> {
+        self.days.iter().flat_map(|d| d.readings.iter())
+    }
+
+    pub fn intensities(&self) -> Vec<f32> {
+        self.readings().map(|r| r.intensity).collect()
+    }
+
+    pub fn directions(&self) -> Vec<Option<f32>> {
+        self.readings().map(|r| r.direction).collect()
+    }
+
+    /// Plain-text summary of what was read and every anomaly with its line.
+    pub fn report(&self, source: &str) -> String {
+        let mut out = format!(
+            "Wind data report for {source}\n{} days, {} readings, {} anomalies\n",
+            self.days.len(),
+            self.readings().count(),
+            self.anomalies.len()
+        );
+        if !self.days.is_empty() {
+            out.push_str("\nDays:\n");
+            for day in &self.days {
+                let label = if day.label.is_empty() { "(none)" } else { &day.label };
+                out.push_str(&format!("  {label:>6}  line {:>4}  {} readings\n", day.line, day.readings.len()));
+            }
+        }
+        if !self.anomalies.is_empty() {
+            out.push_str("\nAnomalies:\n");
+            for a in &self.anomalies {
+                let day = a.day.as_deref().map(|d| format!("day {d}")).unwrap_or_default();
+                out.push_str(&format!("  line {:>4}  {day:<8}  {}\n", a.line, a.kind));
+            }
+        }
+        out
+    }
+}
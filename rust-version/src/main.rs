@@ -10,6 +10,18 @@ use std::fs;
 
 mod converters;
 use converters::Progress;
+use converters::audio::LoopSettings;
+use converters::bpm::{BpmOptions, ClassicVoice, HeartVoice, ParametricVoice, SampledVoice, Variability};
+use converters::bpm_video::BpmVideo;
+use converters::color::ColorSpace;
+use converters::rgb_layout::RgbLayout;
+use converters::palette::PaletteStyle;
+use converters::compose::{Track, TrackSource};
+use converters::gust::{AccentSound, GustSettings};
+use converters::interp::Interpolation;
+use converters::rgb::RgbOptions;
+use converters::shared::{Timeline, MAX_TIMELINE_SECS};
+use converters::wind::{GainCurve, SpeakerLayout, WindFilter, WindMapping, WindOptions, WindSource};
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 enum ConversionType {
@@ -19,6 +31,7 @@ enum ConversionType {
     Rgb,
     Text,
     Slideshow,
+    Mix,
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -27,6 +40,44 @@ enum CloudsFolderMode {
     StitchImages,
 }
 
+#[derive(PartialEq, Clone, Copy)]
+enum WindCurve {
+    Linear,
+    Logarithmic,
+    Exponential,
+    Table,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum VoiceKind {
+    Classic,
+    Parametric,
+    Samples,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum MixKind {
+    Wind,
+    Bpm,
+}
+
+/// A composer track; its synthesis settings are read from its tab at execute time.
+struct MixTrack {
+    pdf: PathBuf,
+    kind: MixKind,
+    gain_db: f32,
+    pan: f32,
+    mute: bool,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum TimelineKind {
+    Fixed,
+    PerReading,
+    PerDay,
+    Compressed,
+}
+
 enum AppMessage {
     Progress(Progress),
     Finished,
@@ -38,8 +89,6 @@ const COLOR_TEXT: egui::Color32 = egui::Color32::from_rgb(33, 37, 13);
 const COLOR_RED: egui::Color32 = egui::Color32::from_rgb(109, 74, 56);
 const COLOR_ACCENT: egui::Color32 = egui::Color32::from_rgb(62, 84, 114);
 const COLOR_FADED: egui::Color32 = egui::Color32::from_rgb(117, 122, 97);
-const COLOR_WHITE: egui::Color32 = egui::Color32::from_rgb(255, 255, 255);
-const COLOR_BLACK: egui::Color32 = egui::Color32::from_rgb(0, 0, 0);
 
 // Draws text inline with other widgets without shifting its container.
 // Vertically centers perfectly via LEFT_CENTER alignment.
@@ -60,6 +109,29 @@ fn retro_label_sized(ui: &mut egui::Ui, text: &str, color: egui::Color32, font_s
     );
 }
 
+// Radio row for choosing how a converter moves between readings
+fn interpolation_row(ui: &mut egui::Ui, value: &mut Interpolation) {
+    retro_label_sized(ui, "> BETWEEN READINGS:", COLOR_TEXT, 12.0);
+    ui.add_space(16.0);
+    let modes = [
+        (Interpolation::Step, "[ STEP ]"),
+        (Interpolation::Linear, "[ LINEAR ]"),
+        (Interpolation::Cosine, "[ COSINE ]"),
+        (Interpolation::MonotoneCubic, "[ CUBIC ]"),
+    ];
+    for (mode, label) in modes {
+        ui.radio_value(value, mode, label);
+    }
+    let smoothing = matches!(value, Interpolation::Smooth(_));
+    if ui.radio(smoothing, "[ SMOOTH ]").clicked() && !smoothing {
+        *value = Interpolation::Smooth(5.0);
+    }
+    if let Interpolation::Smooth(window) = value {
+        ui.add(egui::DragValue::new(window).speed(0.1).clamp_range(1.0..=500.0));
+        retro_label_sized(ui, "READINGS", COLOR_TEXT, 12.0);
+    }
+}
+
 // Convenience wrapper for standard 16.0 font size labels
 fn retro_label(ui: &mut egui::Ui, text: &str, color: egui::Color32) {
     retro_label_sized(ui, text, color, 16.0);
@@ -99,6 +171,36 @@ struct CubeConvertApp {
     rgb_color: [u8; 3],
     color_history: Vec<[u8; 3]>,
     clouds_folder_mode: CloudsFolderMode,
+    wind_source: WindSource,
+    wind_looping: LoopSettings,
+    wind_curve: WindCurve,
+    wind_mapping: WindMapping,
+    wind_filter: WindFilter,
+    wind_directions: bool,
+    wind_layout: SpeakerLayout,
+    wind_gusts: GustSettings,
+    wind_table_text: String,
+    timeline_kind: TimelineKind,
+    timeline_fixed_secs: f32,
+    timeline_reading_secs: f32,
+    timeline_day_secs: f32,
+    timeline_ratio: f32,
+    wind_interpolation: Interpolation,
+    bpm_interpolation: Interpolation,
+    bpm_voice: VoiceKind,
+    bpm_classic: ClassicVoice,
+    bpm_parametric: ParametricVoice,
+    bpm_sampled: SampledVoice,
+    bpm_variability: Variability,
+    bpm_video: BpmVideo,
+    rgb_interpolation: Interpolation,
+    rgb_space: ColorSpace,
+    rgb_layout: RgbLayout,
+    rgb_width: u32,
+    rgb_height: u32,
+    rgb_palette: PaletteStyle,
+    export_midi: bool,
+    mix_tracks: Vec<MixTrack>,
     status_msg: String,
     show_error_popup: bool,
     popup_error_msg: String,
@@ -109,6 +211,7 @@ struct CubeConvertApp {
     progress_total: usize,
     file_fractions: HashMap<String, f32>,
     current_file: String,
+    warnings: Vec<(String, String)>,
 
     // Concurrency
     tx: crossbeam_channel::Sender<AppMessage>,
@@ -156,8 +259,9 @@ impl CubeConvertApp {
         tab_animations.insert(ConversionType::Rgb, 0.0);
         tab_animations.insert(ConversionType::Text, 0.0);
         tab_animations.insert(ConversionType::Slideshow, 0.0);
+        tab_animations.insert(ConversionType::Mix, 0.0);
 
-        let mut app = Self {
+        let app = Self {
             selected_tab: ConversionType::Wind,
             selected_path: None,
             last_dir: None,
@@ -166,6 +270,36 @@ impl CubeConvertApp {
             rgb_color,
             color_history,
             clouds_folder_mode: CloudsFolderMode::BatchPdf,
+            wind_source: WindSource::Loop,
+            wind_looping: LoopSettings::default(),
+            wind_curve: WindCurve::Linear,
+            wind_mapping: WindMapping::default(),
+            wind_filter: WindFilter::default(),
+            wind_directions: false,
+            wind_layout: SpeakerLayout::Stereo,
+            wind_gusts: GustSettings::default(),
+            wind_table_text: "0:0, 2:0.1, 6:0.5, 15:1".to_string(),
+            timeline_kind: TimelineKind::Fixed,
+            timeline_fixed_secs: 12.0 * 60.0,
+            timeline_reading_secs: 2.0,
+            timeline_day_secs: 30.0,
+            timeline_ratio: 1440.0,
+            wind_interpolation: Interpolation::default(),
+            bpm_interpolation: Interpolation::default(),
+            bpm_voice: VoiceKind::Classic,
+            bpm_classic: ClassicVoice::default(),
+            bpm_parametric: ParametricVoice::default(),
+            bpm_sampled: SampledVoice::default(),
+            bpm_variability: Variability::default(),
+            bpm_video: BpmVideo::Off,
+            rgb_interpolation: Interpolation::default(),
+            rgb_space: ColorSpace::default(),
+            rgb_layout: RgbLayout::default(),
+            rgb_width: 520,
+            rgb_height: 520,
+            rgb_palette: PaletteStyle::default(),
+            export_midi: false,
+            mix_tracks: Vec::new(),
             status_msg: String::new(),
             show_error_popup: false,
             popup_error_msg: String::new(),
@@ -174,6 +308,7 @@ impl CubeConvertApp {
             progress_total: 0,
             file_fractions: HashMap::new(),
             current_file: String::new(),
+            warnings: Vec::new(),
             tx,
             rx,
             cancel_flag: Arc::new(AtomicBool::new(false)),
@@ -338,10 +473,8 @@ impl CubeConvertApp {
         if ui.is_rect_visible(rect) {
             let bg_color = if is_selected {
                 COLOR_TEXT
-            } else if !enabled {
-                COLOR_FADED // darken when disabled
-            } else if response.hovered() {
-                COLOR_FADED
+            } else if !enabled || response.hovered() {
+                COLOR_FADED // darken when disabled or hovered
             } else {
                 COLOR_BG
             };
@@ -469,6 +602,9 @@ impl eframe::App for CubeConvertApp {
                             self.current_file.clear();
                         }
                     }
+                    Progress::Warning { name, message } => {
+                        self.warnings.push((name, message));
+                    }
                     Progress::Error { name, error } => {
                         if name != "Batch" {
                             self.progress_current += 1;
@@ -585,8 +721,13 @@ impl eframe::App for CubeConvertApp {
 
                             ui.add_space(16.0);
                             if ui.add(egui::Button::new("[ OPEN DIR ]").fill(COLOR_BG)).clicked() {
-                                if let Some(path) = &self.selected_path {
-                                    let dir = if self.is_folder {
+                                // The mix lands next to its first track
+                                let opened = match self.selected_tab {
+                                    ConversionType::Mix => self.mix_tracks.iter().find(|t| !t.mute).map(|t| &t.pdf),
+                                    _ => self.selected_path.as_ref(),
+                                };
+                                if let Some(path) = opened {
+                                    let dir = if self.is_folder && self.selected_tab != ConversionType::Mix {
                                         path.clone()
                                     } else {
                                         path.parent().unwrap_or(Path::new("")).to_path_buf()
@@ -599,15 +740,27 @@ impl eframe::App for CubeConvertApp {
                                     let _ = std::process::Command::new("xdg-open").arg(dir).spawn();
                                 }
                             }
+                            if !self.warnings.is_empty() {
+                                ui.add_space(16.0);
+                                let mut files: Vec<&str> = self.warnings.iter().map(|(name, _)| name.as_str()).collect();
+                                files.sort_unstable();
+                                files.dedup();
+                                let warning = match (self.warnings.as_slice(), files.as_slice()) {
+                                    ([(name, message)], _) => format!("{name}: {message}"),
+                                    (all, [name]) => format!("{name} finished with {} warnings.", all.len()),
+                                    (_, files) => format!("{} files finished with warnings.", files.len()),
+                                };
+                                retro_label_sized(ui, &warning, COLOR_ACCENT, 10.0);
+                            }
                         } else {
-                            let text = if self.status_msg == "Cancelled." || self.status_msg == "ABORTING..." {
-                                format!("> {}{}", self.status_msg, cursor)
-                            } else if self.status_msg.starts_with("Error") || self.status_msg.starts_with("An error") {
-                                format!("> {}{}", self.status_msg, cursor)
-                            } else if !self.status_msg.is_empty() {
+                            let text = if !self.status_msg.is_empty() {
                                 format!("> {}{}", self.status_msg, cursor)
                             } else {
-                                let prompt = if self.selected_path.is_some() {
+                                let ready = match self.selected_tab {
+                                    ConversionType::Mix => !self.mix_tracks.is_empty(),
+                                    _ => self.selected_path.is_some(),
+                                };
+                                let prompt = if ready {
                                     "> READY. CLICK EXECUTE TO START."
                                 } else {
                                     "> AWAITING DATA DROP OR SELECTION..."
@@ -628,7 +781,10 @@ impl eframe::App for CubeConvertApp {
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if !self.is_converting {
-                        let exec_enabled = self.selected_path.is_some() && (self.selected_tab != ConversionType::Slideshow || self.is_folder);
+                        let exec_enabled = match self.selected_tab {
+                            ConversionType::Mix => self.mix_tracks.iter().any(|t| !t.mute),
+                            _ => self.selected_path.is_some() && (self.selected_tab != ConversionType::Slideshow || self.is_folder),
+                        };
                         let exec_fill = if exec_enabled { COLOR_BG } else { COLOR_FADED };
                         
                         let mut btn_exec = egui::Button::new(egui::RichText::new("EXECUTE").size(18.0).strong().color(COLOR_TEXT))
@@ -663,6 +819,7 @@ impl eframe::App for CubeConvertApp {
                 if self.custom_tab(ui, ConversionType::Rgb, "RGB", ctx) { self.selected_tab = ConversionType::Rgb; tab_changed = true; }
                 if self.custom_tab(ui, ConversionType::Slideshow, "SLIDE", ctx) { self.selected_tab = ConversionType::Slideshow; tab_changed = true; }
                 if self.custom_tab(ui, ConversionType::Text, "TEXT", ctx) { self.selected_tab = ConversionType::Text; tab_changed = true; }
+                if self.custom_tab(ui, ConversionType::Mix, "MIX", ctx) { self.selected_tab = ConversionType::Mix; tab_changed = true; }
             });
 
             if tab_changed {
@@ -689,6 +846,7 @@ impl eframe::App for CubeConvertApp {
                 ConversionType::Rgb       => "Convert RGB values (PDF) -> gradient MP4",
                 ConversionType::Text      => "Convert text (PDF) -> scrolling text MP4",
                 ConversionType::Slideshow => "Folder of images -> 4s Slideshow MP4",
+                ConversionType::Mix       => "Layer wind and BPM data (PDFs) -> one mixed MP3",
             };
             
             let char_count = (self.time_active * 30.0) as usize;
@@ -707,8 +865,9 @@ impl eframe::App for CubeConvertApp {
                  
                  let enabled = !self.is_converting;
                  
-                 // Hide SELECT FILE if slideshow is selected to prevent confusion
-                 if self.selected_tab != ConversionType::Slideshow {
+                 // Hide SELECT FILE if slideshow is selected to prevent confusion,
+                 // the mix adds its tracks in its own panel instead
+                 if !matches!(self.selected_tab, ConversionType::Slideshow | ConversionType::Mix) {
                      let fill = if enabled { COLOR_BG } else { COLOR_FADED };
                      let mut btn_file = egui::Button::new(egui::RichText::new("[ SELECT FILE ]").color(COLOR_TEXT)).fill(fill);
                      if !enabled { btn_file = btn_file.sense(egui::Sense::hover()); }
@@ -731,7 +890,7 @@ impl eframe::App for CubeConvertApp {
                  let mut btn_folder = egui::Button::new(egui::RichText::new("[ SELECT FOLDER ]").color(COLOR_TEXT)).fill(folder_fill);
                  if !enabled { btn_folder = btn_folder.sense(egui::Sense::hover()); }
                  
-                 if ui.add(btn_folder).clicked() && enabled {
+                 if self.selected_tab != ConversionType::Mix && ui.add(btn_folder).clicked() && enabled {
                      let mut dialog = FileDialog::new();
                      if let Some(dir) = &self.last_dir { dialog = dialog.set_directory(dir); }
                      if let Some(path) = dialog.pick_folder() {
@@ -748,7 +907,10 @@ impl eframe::App for CubeConvertApp {
 
             ui.horizontal(|ui| {
                  ui.add_space(24.0);
-                 if let Some(path) = &self.selected_path {
+                 if self.selected_tab == ConversionType::Mix {
+                    let live = self.mix_tracks.iter().filter(|t| !t.mute).count();
+                    ui.label(egui::RichText::new(format!("> {} TRACKS, {live} PLAYING.", self.mix_tracks.len())).color(COLOR_TEXT));
+                 } else if let Some(path) = &self.selected_path {
                     egui::Frame::none()
                         .fill(COLOR_BG)
                         .stroke(egui::Stroke::new(2.0, COLOR_TEXT))
@@ -769,81 +931,478 @@ impl eframe::App for CubeConvertApp {
             
             ui.add_space(32.0);
 
-            if self.selected_tab == ConversionType::Clouds && self.is_folder {
-                ui.horizontal(|ui| {
-                     ui.add_space(24.0);
-                     egui::Frame::none()
-                        .stroke(egui::Stroke::new(2.0, COLOR_TEXT))
-                        .inner_margin(egui::Margin::symmetric(16.0, 12.0))
-                        .show(ui, |ui| {
-                            let enabled = !self.is_converting;
-                            ui.add_enabled_ui(enabled, |ui| {
-                                ui.vertical(|ui| {
-                                    ui.horizontal(|ui| {
-                                        retro_label_sized(ui, "> CLOUD DIRECTORY MODE:", COLOR_TEXT, 12.0);
-                                        ui.add_space(16.0);
-                                        ui.radio_value(&mut self.clouds_folder_mode, CloudsFolderMode::BatchPdf, "[ BATCH ]");
-                                        ui.add_space(16.0);
-                                        ui.radio_value(&mut self.clouds_folder_mode, CloudsFolderMode::StitchImages, "[ STITCH ]");
+            // The option panels outgrow the window for some converters
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                let timed = matches!(
+                    self.selected_tab,
+                    ConversionType::Wind | ConversionType::Bpm | ConversionType::Clouds | ConversionType::Rgb | ConversionType::Mix
+                );
+                if timed {
+                    ui.horizontal(|ui| {
+                        ui.add_space(24.0);
+                        egui::Frame::none()
+                            .stroke(egui::Stroke::new(2.0, COLOR_TEXT))
+                            .inner_margin(egui::Margin::symmetric(16.0, 12.0))
+                            .show(ui, |ui| {
+                                let enabled = !self.is_converting;
+                                ui.add_enabled_ui(enabled, |ui| {
+                                    ui.vertical(|ui| {
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> TIMELINE:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.radio_value(&mut self.timeline_kind, TimelineKind::Fixed, "[ FIXED ]");
+                                            ui.radio_value(&mut self.timeline_kind, TimelineKind::PerReading, "[ PER READING ]");
+                                            ui.radio_value(&mut self.timeline_kind, TimelineKind::PerDay, "[ PER DAY ]");
+                                            ui.radio_value(&mut self.timeline_kind, TimelineKind::Compressed, "[ COMPRESS ]");
+                                            ui.add_space(16.0);
+                                            // Derived lengths are capped per file by the converter, which warns
+                                            let (value, unit, range) = match self.timeline_kind {
+                                                TimelineKind::Fixed      => (&mut self.timeline_fixed_secs, "S TOTAL", 1.0..=MAX_TIMELINE_SECS as f32),
+                                                TimelineKind::PerReading => (&mut self.timeline_reading_secs, "S / READING", 0.01..=100000.0),
+                                                TimelineKind::PerDay     => (&mut self.timeline_day_secs, "S / DAY", 0.01..=100000.0),
+                                                TimelineKind::Compressed => (&mut self.timeline_ratio, ": 1", 0.01..=100000.0),
+                                            };
+                                            ui.add(egui::DragValue::new(value).speed(0.5).clamp_range(range));
+                                            retro_label_sized(ui, unit, COLOR_TEXT, 12.0);
+                                        });
+                                        let interpolation = match self.selected_tab {
+                                            ConversionType::Wind => Some(&mut self.wind_interpolation),
+                                            ConversionType::Bpm  => Some(&mut self.bpm_interpolation),
+                                            ConversionType::Rgb  => Some(&mut self.rgb_interpolation),
+                                            _ => None,
+                                        };
+                                        if let Some(interpolation) = interpolation {
+                                            ui.add_space(6.0);
+                                            ui.horizontal(|ui| interpolation_row(ui, interpolation));
+                                            ui.add_space(6.0);
+                                            ui.horizontal(|ui| {
+                                                retro_label_sized(ui, "> MIDI:", COLOR_TEXT, 12.0);
+                                                ui.add_space(16.0);
+                                                ui.checkbox(&mut self.export_midi, "WRITE .MID FOR A DAW");
+                                            });
+                                        }
                                     });
-                                    ui.add_space(6.0);
-                                    let desc = if self.clouds_folder_mode == CloudsFolderMode::BatchPdf {
-                                        "Process each PDF into its own separate video."
-                                    } else {
-                                        "Stitch images into one continuous scrolling video."
-                                    };
-                                    retro_label_sized(ui, desc, COLOR_TEXT, 10.0);
                                 });
                             });
-                        });
-                });
-            }
+                    });
+                    ui.add_space(12.0);
+                }
 
-            if self.selected_tab == ConversionType::Text {
-                ui.horizontal(|ui| {
-                    ui.add_space(24.0);
-                    egui::Frame::none()
-                        .stroke(egui::Stroke::new(2.0, COLOR_TEXT))
-                        .inner_margin(egui::Margin::symmetric(16.0, 12.0))
-                        .show(ui, |ui| {
-                        let enabled = !self.is_converting;
-                        ui.add_enabled_ui(enabled, |ui| {
-                            ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                                ui.scope(|ui| {
-                                    ui.spacing_mut().interact_size = egui::vec2(40.0, 24.0);
-                                    let response = ui.color_edit_button_srgb(&mut self.rgb_color);
-                                    
-                                    // Draw our own clean border over egui's default button
-                                    let color32 = egui::Color32::from_rgb(self.rgb_color[0], self.rgb_color[1], self.rgb_color[2]);
-                                    ui.painter().rect(response.rect, 0.0, color32, egui::Stroke::new(2.0, COLOR_TEXT));
+                if self.selected_tab == ConversionType::Clouds && self.is_folder {
+                    ui.horizontal(|ui| {
+                         ui.add_space(24.0);
+                         egui::Frame::none()
+                            .stroke(egui::Stroke::new(2.0, COLOR_TEXT))
+                            .inner_margin(egui::Margin::symmetric(16.0, 12.0))
+                            .show(ui, |ui| {
+                                let enabled = !self.is_converting;
+                                ui.add_enabled_ui(enabled, |ui| {
+                                    ui.vertical(|ui| {
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> CLOUD DIRECTORY MODE:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.radio_value(&mut self.clouds_folder_mode, CloudsFolderMode::BatchPdf, "[ BATCH ]");
+                                            ui.add_space(16.0);
+                                            ui.radio_value(&mut self.clouds_folder_mode, CloudsFolderMode::StitchImages, "[ STITCH ]");
+                                        });
+                                        ui.add_space(6.0);
+                                        let desc = if self.clouds_folder_mode == CloudsFolderMode::BatchPdf {
+                                            "Process each PDF into its own separate video."
+                                        } else {
+                                            "Stitch images into one continuous scrolling video."
+                                        };
+                                        retro_label_sized(ui, desc, COLOR_TEXT, 10.0);
+                                    });
+                                });
+                            });
+                    });
+                }
+
+                if self.selected_tab == ConversionType::Wind {
+                    ui.horizontal(|ui| {
+                        ui.add_space(24.0);
+                        egui::Frame::none()
+                            .stroke(egui::Stroke::new(2.0, COLOR_TEXT))
+                            .inner_margin(egui::Margin::symmetric(16.0, 12.0))
+                            .show(ui, |ui| {
+                                let enabled = !self.is_converting;
+                                ui.add_enabled_ui(enabled, |ui| {
+                                    ui.vertical(|ui| {
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> WIND SOURCE:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.radio_value(&mut self.wind_source, WindSource::Loop, "[ LOOP ]");
+                                            ui.radio_value(&mut self.wind_source, WindSource::Layers, "[ LAYERS ]");
+                                            ui.radio_value(&mut self.wind_source, WindSource::Procedural, "[ SYNTH ]");
+                                        });
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            let l = &mut self.wind_looping;
+                                            if self.wind_source == WindSource::Procedural {
+                                                retro_label_sized(ui, "SEED:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut l.seed));
+                                                return;
+                                            }
+                                            retro_label_sized(ui, "SEAM MS:", COLOR_TEXT, 12.0);
+                                            ui.add(egui::DragValue::new(&mut l.crossfade_ms).speed(5.0).clamp_range(0.0..=2000.0));
+                                            ui.checkbox(&mut l.auto_detect, "AUTO LOOP POINTS");
+                                            ui.checkbox(&mut l.random_offsets, "RANDOM OFFSETS");
+                                            if l.random_offsets {
+                                                retro_label_sized(ui, "SEED:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut l.seed));
+                                            }
+                                        });
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> GAIN CURVE:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.radio_value(&mut self.wind_curve, WindCurve::Linear, "[ LIN ]");
+                                            ui.radio_value(&mut self.wind_curve, WindCurve::Logarithmic, "[ LOG ]");
+                                            ui.radio_value(&mut self.wind_curve, WindCurve::Exponential, "[ EXP ]");
+                                            ui.radio_value(&mut self.wind_curve, WindCurve::Table, "[ TABLE ]");
+                                        });
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            let m = &mut self.wind_mapping;
+                                            retro_label_sized(ui, "RANGE:", COLOR_TEXT, 12.0);
+                                            ui.add(egui::DragValue::new(&mut m.input_min).speed(0.1).clamp_range(0.0..=1000.0));
+                                            ui.add(egui::DragValue::new(&mut m.input_max).speed(0.1).clamp_range(0.1..=1000.0));
+                                            retro_label_sized(ui, "SILENT <=", COLOR_TEXT, 12.0);
+                                            ui.add(egui::DragValue::new(&mut m.silence_threshold).speed(0.1).clamp_range(-1.0..=1000.0));
+                                            retro_label_sized(ui, "LEVEL:", COLOR_TEXT, 12.0);
+                                            ui.add(egui::DragValue::new(&mut m.ceiling).speed(0.01).clamp_range(0.0..=1.0));
+                                        });
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "SCALE:", COLOR_TEXT, 12.0);
+                                            let presets = [("[ 0-15 ]", 15.0, 1.0), ("[ BEAUFORT ]", 12.0, 1.0), ("[ KM/H ]", 120.0, 5.0), ("[ 0-10 ]", 10.0, 1.0)];
+                                            for (label, max, threshold) in presets {
+                                                if ui.add(egui::Button::new(label).fill(COLOR_BG)).clicked() {
+                                                    self.wind_mapping.input_min = 0.0;
+                                                    self.wind_mapping.input_max = max;
+                                                    self.wind_mapping.silence_threshold = threshold;
+                                                }
+                                            }
+                                        });
+                                        if self.wind_curve == WindCurve::Table {
+                                            ui.add_space(6.0);
+                                            ui.horizontal(|ui| {
+                                                retro_label_sized(ui, "READING:GAIN", COLOR_TEXT, 12.0);
+                                                ui.text_edit_singleline(&mut self.wind_table_text);
+                                            });
+                                        }
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> DIRECTION:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.checkbox(&mut self.wind_directions, "READINGS HAVE DIRECTION");
+                                            ui.add_enabled_ui(self.wind_directions, |ui| {
+                                                ui.radio_value(&mut self.wind_layout, SpeakerLayout::Stereo, "[ STEREO ]");
+                                                ui.radio_value(&mut self.wind_layout, SpeakerLayout::Quad, "[ QUAD ]");
+                                                ui.radio_value(&mut self.wind_layout, SpeakerLayout::Surround51, "[ 5.1 ]");
+                                                ui.radio_value(&mut self.wind_layout, SpeakerLayout::Octagon, "[ OCTAGON ]");
+                                            });
+                                        });
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            let g = &mut self.wind_gusts;
+                                            retro_label_sized(ui, "> GUSTS:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.checkbox(&mut g.enabled, "ACCENTS");
+                                            ui.add_enabled_ui(g.enabled, |ui| {
+                                                retro_label_sized(ui, "ABOVE:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut g.threshold).speed(0.1).clamp_range(0.0..=1000.0));
+                                                retro_label_sized(ui, "JUMP:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut g.jump).speed(0.1).clamp_range(0.1..=1000.0));
+                                                ui.radio_value(&mut g.sound, AccentSound::Swell, "[ SWELL ]");
+                                                ui.radio_value(&mut g.sound, AccentSound::Samples, "[ SAMPLES ]");
+                                                retro_label_sized(ui, "LEVEL:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut g.level).speed(0.05).clamp_range(0.0..=8.0));
+                                            });
+                                        });
+                                        if self.wind_source != WindSource::Procedural {
+                                            ui.add_space(6.0);
+                                            ui.horizontal(|ui| {
+                                                let f = &mut self.wind_filter;
+                                                ui.checkbox(&mut f.enabled, "TONE FILTER");
+                                                ui.add_enabled_ui(f.enabled, |ui| {
+                                                    retro_label_sized(ui, "CUTOFF HZ:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut f.cutoff_min).speed(10.0).clamp_range(20.0..=20000.0));
+                                                    ui.add(egui::DragValue::new(&mut f.cutoff_max).speed(10.0).clamp_range(20.0..=20000.0));
+                                                    retro_label_sized(ui, "RES:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut f.resonance).speed(0.01).clamp_range(0.5..=8.0));
+                                                    retro_label_sized(ui, "SHELF DB:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut f.shelf_db).speed(0.1).clamp_range(0.0..=12.0));
+                                                });
+                                            });
+                                        }
+                                    });
                                 });
+                            });
+                    });
+                }
+
+                if self.selected_tab == ConversionType::Bpm {
+                    ui.horizontal(|ui| {
+                        ui.add_space(24.0);
+                        egui::Frame::none()
+                            .stroke(egui::Stroke::new(2.0, COLOR_TEXT))
+                            .inner_margin(egui::Margin::symmetric(16.0, 12.0))
+                            .show(ui, |ui| {
+                                let enabled = !self.is_converting;
+                                ui.add_enabled_ui(enabled, |ui| {
+                                    ui.vertical(|ui| {
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> HEART VOICE:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.radio_value(&mut self.bpm_voice, VoiceKind::Classic, "[ CLASSIC ]");
+                                            ui.radio_value(&mut self.bpm_voice, VoiceKind::Parametric, "[ MODEL ]");
+                                            ui.radio_value(&mut self.bpm_voice, VoiceKind::Samples, "[ SAMPLES ]");
+                                        });
+                                        ui.add_space(6.0);
+                                        match self.bpm_voice {
+                                            VoiceKind::Classic => {
+                                                ui.horizontal(|ui| {
+                                                    let v = &mut self.bpm_classic;
+                                                    retro_label_sized(ui, "LUB HZ:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.lub_hz).speed(0.5).clamp_range(20.0..=200.0));
+                                                    retro_label_sized(ui, "DUB HZ:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.dub_hz).speed(0.5).clamp_range(20.0..=200.0));
+                                                    retro_label_sized(ui, "DUB LEVEL:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.dub_level).speed(0.01).clamp_range(0.0..=2.0));
+                                                });
+                                            }
+                                            VoiceKind::Parametric => {
+                                                let v = &mut self.bpm_parametric;
+                                                ui.horizontal(|ui| {
+                                                    retro_label_sized(ui, "PITCH HZ:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.pitch_hz).speed(0.5).clamp_range(20.0..=200.0));
+                                                    retro_label_sized(ui, "DROP X:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.pitch_drop).speed(0.01).clamp_range(1.0..=4.0));
+                                                    retro_label_sized(ui, "DECAY MS:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.decay_ms).speed(1.0).clamp_range(5.0..=400.0));
+                                                    retro_label_sized(ui, "CLICK:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.click).speed(0.01).clamp_range(0.0..=1.0));
+                                                });
+                                                ui.add_space(6.0);
+                                                ui.horizontal(|ui| {
+                                                    retro_label_sized(ui, "BODY HZ:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.body_hz).speed(1.0).clamp_range(30.0..=1000.0));
+                                                    retro_label_sized(ui, "BODY Q:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.body_q).speed(0.05).clamp_range(0.5..=20.0));
+                                                    retro_label_sized(ui, "DUB LEVEL:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.dub_level).speed(0.01).clamp_range(0.0..=2.0));
+                                                });
+                                            }
+                                            VoiceKind::Samples => {
+                                                ui.horizontal(|ui| {
+                                                    let v = &mut self.bpm_sampled;
+                                                    for (label, path) in [("[ LUB WAV ]", &mut v.lub), ("[ DUB WAV ]", &mut v.dub)] {
+                                                        if ui.add(egui::Button::new(label).fill(COLOR_BG)).clicked() {
+                                                            let mut dialog = FileDialog::new().add_filter("WAV", &["wav"]);
+                                                            if let Some(dir) = path.parent().filter(|d| d.is_dir()) { dialog = dialog.set_directory(dir); }
+                                                            if let Some(picked) = dialog.pick_file() { *path = picked; }
+                                                        }
+                                                        let file = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                                                        retro_label_sized(ui, &file, COLOR_TEXT, 12.0);
+                                                        ui.add_space(8.0);
+                                                    }
+                                                    retro_label_sized(ui, "DUB LEVEL:", COLOR_TEXT, 12.0);
+                                                    ui.add(egui::DragValue::new(&mut v.dub_level).speed(0.01).clamp_range(0.0..=2.0));
+                                                });
+                                            }
+                                        }
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            let v = &mut self.bpm_variability;
+                                            retro_label_sized(ui, "> VARIABILITY:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.checkbox(&mut v.enabled, "NATURAL");
+                                            ui.add_enabled_ui(v.enabled, |ui| {
+                                                retro_label_sized(ui, "HRV MS:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut v.hrv_ms).speed(0.5).clamp_range(0.0..=200.0));
+                                                retro_label_sized(ui, "RSA MS:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut v.rsa_ms).speed(0.5).clamp_range(0.0..=200.0));
+                                                retro_label_sized(ui, "BREATHS/MIN:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut v.breaths_per_min).speed(0.1).clamp_range(4.0..=40.0));
+                                                retro_label_sized(ui, "JITTER MS:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut v.jitter_ms).speed(0.1).clamp_range(0.0..=50.0));
+                                                retro_label_sized(ui, "SEED:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut v.seed));
+                                            });
+                                        });
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> VIDEO:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.radio_value(&mut self.bpm_video, BpmVideo::Off, "[ OFF ]");
+                                            ui.radio_value(&mut self.bpm_video, BpmVideo::Pulse, "[ PULSE ]");
+                                            ui.radio_value(&mut self.bpm_video, BpmVideo::Ecg, "[ ECG ]");
+                                        });
+                                    });
+                                });
+                            });
+                    });
+                }
+
+                if self.selected_tab == ConversionType::Rgb {
+                    ui.horizontal(|ui| {
+                        ui.add_space(24.0);
+                        egui::Frame::none()
+                            .stroke(egui::Stroke::new(2.0, COLOR_TEXT))
+                            .inner_margin(egui::Margin::symmetric(16.0, 12.0))
+                            .show(ui, |ui| {
+                                let enabled = !self.is_converting;
+                                ui.add_enabled_ui(enabled, |ui| {
+                                    ui.vertical(|ui| {
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> BLEND IN:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.radio_value(&mut self.rgb_space, ColorSpace::Srgb, "[ SRGB ]");
+                                            ui.radio_value(&mut self.rgb_space, ColorSpace::LinearRgb, "[ LINEAR ]");
+                                            ui.radio_value(&mut self.rgb_space, ColorSpace::Oklab, "[ OKLAB ]");
+                                            ui.radio_value(&mut self.rgb_space, ColorSpace::Oklch, "[ OKLCH ]");
+                                            ui.radio_value(&mut self.rgb_space, ColorSpace::Hsv, "[ HSV ]");
+                                        });
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> LAYOUT:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.radio_value(&mut self.rgb_layout, RgbLayout::Solid, "[ SOLID ]");
+                                            ui.radio_value(&mut self.rgb_layout, RgbLayout::Barcode, "[ BARCODE ]");
+                                            ui.radio_value(&mut self.rgb_layout, RgbLayout::DayGrid, "[ DAY GRID ]");
+                                            ui.radio_value(&mut self.rgb_layout, RgbLayout::Squares, "[ SQUARES ]");
+                                            ui.radio_value(&mut self.rgb_layout, RgbLayout::TimelineBar, "[ TIMELINE ]");
+                                        });
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> SIZE:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            retro_label_sized(ui, "WIDTH:", COLOR_TEXT, 12.0);
+                                            ui.add(egui::DragValue::new(&mut self.rgb_width).speed(2.0).clamp_range(16..=3840));
+                                            retro_label_sized(ui, "HEIGHT:", COLOR_TEXT, 12.0);
+                                            ui.add(egui::DragValue::new(&mut self.rgb_height).speed(2.0).clamp_range(16..=2160));
+                                        });
+                                        ui.add_space(6.0);
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> PALETTE:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            ui.radio_value(&mut self.rgb_palette, PaletteStyle::Off, "[ OFF ]");
+                                            ui.radio_value(&mut self.rgb_palette, PaletteStyle::Strip, "[ STRIP ]");
+                                            ui.radio_value(&mut self.rgb_palette, PaletteStyle::Grid, "[ GRID ]");
+                                            ui.radio_value(&mut self.rgb_palette, PaletteStyle::Barcode, "[ BARCODE ]");
+                                        });
+                                    });
+                                });
+                            });
+                    });
+                    ui.add_space(12.0);
+                }
+
+                if self.selected_tab == ConversionType::Mix {
+                    ui.horizontal(|ui| {
+                        ui.add_space(24.0);
+                        egui::Frame::none()
+                            .stroke(egui::Stroke::new(2.0, COLOR_TEXT))
+                            .inner_margin(egui::Margin::symmetric(16.0, 12.0))
+                            .show(ui, |ui| {
+                                let enabled = !self.is_converting;
+                                ui.add_enabled_ui(enabled, |ui| {
+                                    ui.vertical(|ui| {
+                                        ui.horizontal(|ui| {
+                                            retro_label_sized(ui, "> TRACKS:", COLOR_TEXT, 12.0);
+                                            ui.add_space(16.0);
+                                            for (label, kind) in [("[ + WIND PDF ]", MixKind::Wind), ("[ + BPM PDF ]", MixKind::Bpm)] {
+                                                if ui.add(egui::Button::new(label).fill(COLOR_BG)).clicked() {
+                                                    let mut dialog = FileDialog::new().add_filter("PDF", &["pdf"]);
+                                                    if let Some(dir) = &self.last_dir { dialog = dialog.set_directory(dir); }
+                                                    if let Some(pdf) = dialog.pick_file() {
+                                                        if let Some(parent) = pdf.parent() { self.last_dir = Some(parent.to_path_buf()); }
+                                                        self.mix_tracks.push(MixTrack { pdf, kind, gain_db: 0.0, pan: 0.0, mute: false });
+                                                    }
+                                                }
+                                            }
+                                        });
+                                        let mut removed = None;
+                                        for (i, track) in self.mix_tracks.iter_mut().enumerate() {
+                                            ui.add_space(6.0);
+                                            ui.horizontal(|ui| {
+                                                let kind = match track.kind {
+                                                    MixKind::Wind => "WIND",
+                                                    MixKind::Bpm  => "BPM ",
+                                                };
+                                                retro_label_sized(ui, &format!("{:>2}. {kind}", i + 1), COLOR_TEXT, 12.0);
+                                                let file = track.pdf.file_name().unwrap_or_default().to_string_lossy().to_string();
+                                                retro_label_sized(ui, &file, COLOR_TEXT, 12.0);
+                                                ui.add_space(8.0);
+                                                retro_label_sized(ui, "GAIN DB:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut track.gain_db).speed(0.1).clamp_range(-48.0..=12.0));
+                                                retro_label_sized(ui, "PAN:", COLOR_TEXT, 12.0);
+                                                ui.add(egui::DragValue::new(&mut track.pan).speed(0.01).clamp_range(-1.0..=1.0));
+                                                ui.checkbox(&mut track.mute, "MUTE");
+                                                if ui.add(egui::Button::new("[ X ]").fill(COLOR_BG)).clicked() {
+                                                    removed = Some(i);
+                                                }
+                                            });
+                                        }
+                                        if let Some(i) = removed {
+                                            self.mix_tracks.remove(i);
+                                        }
+                                        ui.add_space(6.0);
+                                        retro_label_sized(ui, "> EACH TRACK USES THE SETTINGS OF ITS OWN TAB.", COLOR_TEXT, 10.0);
+                                    });
+                                });
+                            });
+                    });
+                    ui.add_space(12.0);
+                }
+
+                if self.selected_tab == ConversionType::Text {
+                    ui.horizontal(|ui| {
+                        ui.add_space(24.0);
+                        egui::Frame::none()
+                            .stroke(egui::Stroke::new(2.0, COLOR_TEXT))
+                            .inner_margin(egui::Margin::symmetric(16.0, 12.0))
+                            .show(ui, |ui| {
+                            let enabled = !self.is_converting;
+                            ui.add_enabled_ui(enabled, |ui| {
+                                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                                    ui.scope(|ui| {
+                                        ui.spacing_mut().interact_size = egui::vec2(40.0, 24.0);
+                                        let response = ui.color_edit_button_srgb(&mut self.rgb_color);
+                                    
+                                        // Draw our own clean border over egui's default button
+                                        let color32 = egui::Color32::from_rgb(self.rgb_color[0], self.rgb_color[1], self.rgb_color[2]);
+                                        ui.painter().rect(response.rect, 0.0, color32, egui::Stroke::new(2.0, COLOR_TEXT));
+                                    });
                                 
-                                ui.add_space(24.0);
+                                    ui.add_space(24.0);
                                 
-                                retro_label_sized(ui, "PALETTE:", COLOR_TEXT, 12.0);
+                                    retro_label_sized(ui, "PALETTE:", COLOR_TEXT, 12.0);
 
-                                ui.add_space(8.0);
-                                for color in self.color_history.clone() {
-                                    let (r, g, b) = (color[0], color[1], color[2]);
-                                    let color32 = egui::Color32::from_rgb(r, g, b);
+                                    ui.add_space(8.0);
+                                    for color in self.color_history.clone() {
+                                        let (r, g, b) = (color[0], color[1], color[2]);
+                                        let color32 = egui::Color32::from_rgb(r, g, b);
                                     
-                                    let (rect, response) = ui.allocate_exact_size(egui::vec2(24.0, 24.0), egui::Sense::click());
-                                    if ui.is_rect_visible(rect) {
-                                        ui.painter().rect(rect, 0.0, color32, egui::Stroke::new(2.0, COLOR_TEXT));
-                                    }
+                                        let (rect, response) = ui.allocate_exact_size(egui::vec2(24.0, 24.0), egui::Sense::click());
+                                        if ui.is_rect_visible(rect) {
+                                            ui.painter().rect(rect, 0.0, color32, egui::Stroke::new(2.0, COLOR_TEXT));
+                                        }
                                     
-                                    if response.clicked() {
-                                        self.rgb_color = color;
-                                        self.color_history.retain(|&c| c != color);
-                                        self.color_history.insert(0, color);
-                                        self.save_settings();
+                                        if response.clicked() {
+                                            self.rgb_color = color;
+                                            self.color_history.retain(|&c| c != color);
+                                            self.color_history.insert(0, color);
+                                            self.save_settings();
+                                        }
                                     }
-                                }
+                                });
                             });
                         });
                     });
-                });
-            }
+                }
+            });
         });
 
         // Ensure the drag overlay draws *over* the entire app
@@ -892,7 +1451,60 @@ impl eframe::App for CubeConvertApp {
 }
 
 impl CubeConvertApp {
+    fn timeline(&self) -> Timeline {
+        match self.timeline_kind {
+            TimelineKind::Fixed      => Timeline::Fixed(self.timeline_fixed_secs),
+            TimelineKind::PerReading => Timeline::PerReading(self.timeline_reading_secs),
+            TimelineKind::PerDay     => Timeline::PerDay(self.timeline_day_secs),
+            TimelineKind::Compressed => Timeline::Compressed(self.timeline_ratio),
+        }
+    }
+
+    fn wind_options(&self) -> Result<WindOptions, String> {
+        let mut mapping = self.wind_mapping.clone();
+        mapping.curve = match self.wind_curve {
+            WindCurve::Linear      => GainCurve::Linear,
+            WindCurve::Logarithmic => GainCurve::Logarithmic,
+            WindCurve::Exponential => GainCurve::Exponential,
+            WindCurve::Table       => GainCurve::Table(converters::wind::parse_gain_table(&self.wind_table_text)?),
+        };
+        if mapping.input_max <= mapping.input_min {
+            return Err("Wind input range is empty: the maximum must be above the minimum.".into());
+        }
+        Ok(WindOptions {
+            mapping,
+            source: self.wind_source,
+            looping: self.wind_looping.clone(),
+            filter: self.wind_filter.clone(),
+            directions: self.wind_directions,
+            layout: self.wind_layout,
+            interpolation: self.wind_interpolation,
+            gusts: self.wind_gusts.clone(),
+            midi: self.export_midi,
+        })
+    }
+
+    /// Whether executing needs valid wind settings.
+    fn uses_wind(&self) -> bool {
+        match self.selected_tab {
+            ConversionType::Wind => true,
+            ConversionType::Mix  => self.mix_tracks.iter().any(|t| t.kind == MixKind::Wind && !t.mute),
+            _ => false,
+        }
+    }
+
     fn start_conversion(&mut self, ctx: egui::Context) {
+        let wind_options = match self.wind_options() {
+            Ok(options) => options,
+            Err(_) if !self.uses_wind() => WindOptions::default(),
+            Err(e) => {
+                self.status_msg = "An error occurred.".to_string();
+                self.popup_error_msg = e;
+                self.show_error_popup = true;
+                return;
+            }
+        };
+
         self.is_converting = true;
         self.status_msg = "INITIALIZING...".to_string();
         self.show_error_popup = false;
@@ -901,12 +1513,13 @@ impl CubeConvertApp {
         self.progress_total = 0;
         self.file_fractions.clear();
         self.current_file.clear();
+        self.warnings.clear();
         self.time_active = 0.0;
 
         self.cancel_flag.store(false, Ordering::Relaxed);
         let cancel = self.cancel_flag.clone();
 
-        let path = self.selected_path.clone().unwrap();
+        let path = self.selected_path.clone().unwrap_or_default();
         let is_folder = self.is_folder;
         let clouds_stitch = self.clouds_folder_mode == CloudsFolderMode::StitchImages;
         let tab = match self.selected_tab {
@@ -916,8 +1529,44 @@ impl CubeConvertApp {
             ConversionType::Rgb       => 3,
             ConversionType::Text      => 4,
             ConversionType::Slideshow => 5,
+            ConversionType::Mix       => 6,
         };
         let color = self.rgb_color;
+        let timeline = self.timeline();
+        let bpm_options = BpmOptions {
+            interpolation: self.bpm_interpolation,
+            voice: match self.bpm_voice {
+                VoiceKind::Classic    => HeartVoice::Classic(self.bpm_classic.clone()),
+                VoiceKind::Parametric => HeartVoice::Parametric(self.bpm_parametric.clone()),
+                VoiceKind::Samples    => HeartVoice::Samples(self.bpm_sampled.clone()),
+            },
+            variability: self.bpm_variability.clone(),
+            midi: self.export_midi,
+            video: self.bpm_video,
+        };
+        let rgb_options = RgbOptions {
+            interpolation: self.rgb_interpolation,
+            space: self.rgb_space,
+            layout: self.rgb_layout,
+            width: self.rgb_width,
+            height: self.rgb_height,
+            palette: self.rgb_palette,
+            midi: self.export_midi,
+        };
+        let mix_tracks: Vec<Track> = self
+            .mix_tracks
+            .iter()
+            .map(|t| Track {
+                pdf: t.pdf.clone(),
+                source: match t.kind {
+                    MixKind::Wind => TrackSource::Wind(wind_options.clone()),
+                    MixKind::Bpm  => TrackSource::Bpm(bpm_options.clone()),
+                },
+                gain_db: t.gain_db,
+                pan: t.pan,
+                mute: t.mute,
+            })
+            .collect();
 
         let (prog_tx, prog_rx) = crossbeam_channel::unbounded::<Progress>();
 
@@ -933,12 +1582,13 @@ impl CubeConvertApp {
         let tx_done = self.tx.clone();
         thread::spawn(move || {
             let result = match tab {
-                0 => converters::convert_wind(&path, is_folder, prog_tx.clone(), cancel.clone()),
-                1 => converters::convert_bpm(&path, is_folder, prog_tx.clone(), cancel.clone()),
-                2 => converters::convert_clouds(&path, is_folder, clouds_stitch, prog_tx.clone(), cancel.clone()),
-                3 => converters::convert_rgb(&path, is_folder, prog_tx.clone(), cancel.clone()),
+                0 => converters::convert_wind(&path, is_folder, wind_options, timeline, prog_tx.clone(), cancel.clone()),
+                1 => converters::convert_bpm(&path, is_folder, bpm_options, timeline, prog_tx.clone(), cancel.clone()),
+                2 => converters::convert_clouds(&path, is_folder, clouds_stitch, timeline, prog_tx.clone(), cancel.clone()),
+                3 => converters::convert_rgb(&path, is_folder, rgb_options, timeline, prog_tx.clone(), cancel.clone()),
                 4 => converters::convert_text(&path, is_folder, color, prog_tx.clone(), cancel.clone()),
                 5 => converters::convert_slideshow(&path, is_folder, prog_tx.clone(), cancel.clone()),
+                6 => converters::convert_mix(&mix_tracks, timeline, prog_tx.clone(), cancel.clone()),
                 _ => Err("Unknown mode".into()),
             };
 